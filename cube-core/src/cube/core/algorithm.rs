@@ -0,0 +1,127 @@
+use crate::cube::{
+    slice::CubeMove,
+    core::grid::{GridSide, MoveDirection}
+};
+
+// whole cube rotations and wide moves expressed with the layers they turn,
+// each layer given with the direction it turns for a clockwise token
+fn expand_token(token: &str) -> Option<Vec<(GridSide, MoveDirection)>> {
+    use GridSide::*;
+    use MoveDirection::*;
+
+    let expanded = match token {
+        "x" => vec![(Right, Clockwise), (MiddleX, CounterClockwise), (Left, CounterClockwise)],
+        "y" => vec![(Top, Clockwise), (MiddleY, CounterClockwise), (Bottom, CounterClockwise)],
+        "z" => vec![(Front, Clockwise), (MiddleZ, Clockwise), (Back, CounterClockwise)],
+        "r" | "Rw" => vec![(Right, Clockwise), (MiddleX, CounterClockwise)],
+        "l" | "Lw" => vec![(Left, Clockwise), (MiddleX, Clockwise)],
+        "u" | "Uw" => vec![(Top, Clockwise), (MiddleY, CounterClockwise)],
+        "d" | "Dw" => vec![(Bottom, Clockwise), (MiddleY, Clockwise)],
+        "f" | "Fw" => vec![(Front, Clockwise), (MiddleZ, Clockwise)],
+        "b" | "Bw" => vec![(Back, Clockwise), (MiddleZ, CounterClockwise)],
+        _ => return None,
+    };
+
+    Some(expanded)
+}
+
+fn parse_token(token: &str) -> Result<Vec<CubeMove>, String> {
+    let (base, suffix) = match token.find(['\'', '2']) {
+        Some(idx) => token.split_at(idx),
+        None => (token, ""),
+    };
+
+    let direction = match suffix {
+        "" => MoveDirection::Clockwise,
+        "'" => MoveDirection::CounterClockwise,
+        "2" | "2'" | "'2" => MoveDirection::Double,
+        _ => return Err(format!("Incorrect move '{}'", token)),
+    };
+
+    if let Some(layers) = expand_token(base) {
+        let moves = layers.into_iter()
+            .map(|(side, layer_direction)| {
+                let layer_direction = match direction {
+                    MoveDirection::Clockwise => layer_direction,
+                    MoveDirection::CounterClockwise => layer_direction.flip(),
+                    MoveDirection::Double => MoveDirection::Double,
+                };
+                CubeMove::from_side(side, layer_direction)
+            })
+            .collect();
+        return Ok(moves);
+    }
+
+    if base.len() != 1 {
+        return Err(format!("Incorrect move '{}'", token));
+    }
+    let (side, _) = CubeMove::from_str(base)?;
+
+    Ok(vec![CubeMove::from_side(side, direction)])
+}
+
+// parses standard notation, including wide moves (r, Rw), cube rotations (x, y, z)
+// and brackets used for grouping, into moves the grid can apply
+pub fn parse_algorithm(notation: &str) -> Result<Vec<CubeMove>, String> {
+    let cleaned: String = notation.chars()
+        .map(|c| if matches!(c, '(' | ')' | '[' | ']') { ' ' } else { c })
+        .collect();
+
+    let mut moves = Vec::new();
+    for token in cleaned.split_whitespace() {
+        moves.extend(parse_token(token)?);
+    }
+
+    Ok(moves)
+}
+
+pub fn invert_algorithm(moves: &[CubeMove]) -> Vec<CubeMove> {
+    moves.iter()
+        .rev()
+        .map(|mv| mv.inverse())
+        .collect()
+}
+
+pub fn algorithm_to_string(moves: &[CubeMove]) -> String {
+    moves.iter()
+        .map(|mv| mv.to_notation())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::grid::Grid;
+
+    #[test]
+    fn test_parse_algorithm() {
+        let moves = parse_algorithm("(R U R' U') R2 M' S2'").unwrap();
+        assert_eq!(algorithm_to_string(&moves), "R U R' U' R2 M' S2");
+
+        assert!(parse_algorithm("R U X").is_err());
+        assert!(parse_algorithm("R3").is_err());
+        assert!(parse_algorithm("'").is_err());
+    }
+
+    #[test]
+    fn test_algorithm_and_inverse_cancel() {
+        let moves = parse_algorithm("x' R U' r' F2 y D Lw2 z' B'").unwrap();
+        let mut grid = Grid::new();
+
+        grid.apply_moves(&moves);
+        assert!(!grid.is_solved());
+
+        grid.apply_moves(&invert_algorithm(&moves));
+        assert!(grid.is_solved());
+    }
+
+    #[test]
+    fn test_rotations_keep_cube_solved() {
+        for rotation in ["x", "y'", "z2"] {
+            let mut grid = Grid::new();
+            grid.apply_moves(&parse_algorithm(rotation).unwrap());
+            assert!(grid.is_solved());
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use std::{fs, path::Path};
+
+use crate::{
+    utils::{
+        cube_utils::Color,
+    },
+    cube::{
+        slice::CubeMove,
+        core::{
+            algorithm::parse_algorithm,
+            grid::{Grid, GridSide},
+            predicate::Goal
+        }
+    }
+};
+
+const BUILTIN_ALGORITHMS: &str = include_str!("../../../assets/algorithms.txt");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlgorithmSet {
+    Oll,
+    Pll,
+    F2l,
+    Ocll,
+}
+
+impl AlgorithmSet {
+    pub fn from_name(name: &str) -> Result<AlgorithmSet, String> {
+        match name.to_ascii_uppercase().as_str() {
+            "OLL" => Ok(AlgorithmSet::Oll),
+            "PLL" => Ok(AlgorithmSet::Pll),
+            "F2L" => Ok(AlgorithmSet::F2l),
+            "OCLL" => Ok(AlgorithmSet::Ocll),
+            _ => Err(format!("Unknown algorithm set '{}'", name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlgorithmSet::Oll => "OLL",
+            AlgorithmSet::Pll => "PLL",
+            AlgorithmSet::F2l => "F2L",
+            AlgorithmSet::Ocll => "OCLL",
+        }
+    }
+
+    // what has to hold for a position to be a case of this set
+    fn is_case(&self, grid: &Grid) -> bool {
+        match self {
+            AlgorithmSet::Oll => f2l(grid).is_met(grid),
+            AlgorithmSet::Pll => f2l(grid).and(Goal::FaceSolved(GridSide::Top)).is_met(grid),
+            AlgorithmSet::F2l => Goal::Cross(center(grid, GridSide::Bottom)).is_met(grid),
+            AlgorithmSet::Ocll => f2l(grid).is_met(grid) && are_top_edges_oriented(grid),
+        }
+    }
+
+    // what has to hold after an algorithm of this set was applied
+    fn is_goal(&self, grid: &Grid) -> bool {
+        match self {
+            AlgorithmSet::Oll => f2l(grid).and(Goal::FaceSolved(GridSide::Top)).is_met(grid),
+            AlgorithmSet::Pll => Goal::Solved.up_to_auf().is_met(grid),
+            AlgorithmSet::F2l => f2l(grid).is_met(grid),
+            AlgorithmSet::Ocll => f2l(grid).and(Goal::FaceSolved(GridSide::Top)).is_met(grid),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Algorithm {
+    pub notation: String,
+    pub moves: Vec<CubeMove>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlgorithmCase {
+    pub set: AlgorithmSet,
+    pub name: String,
+    pub setup: Algorithm,
+    pub algorithms: Vec<Algorithm>,
+}
+
+impl AlgorithmCase {
+    pub fn setup_grid(&self) -> Grid {
+        let mut grid = Grid::new();
+        grid.apply_moves(&self.setup.moves);
+        grid
+    }
+
+    fn validate(&self, algorithm: &Algorithm) -> Result<(), String> {
+        let mut grid = self.setup_grid();
+        grid.apply_moves(&algorithm.moves);
+
+        if self.set.is_goal(&grid) {
+            Ok(())
+        } else {
+            Err(format!("'{}' does not solve {} {}", algorithm.notation, self.set.name(), self.name))
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AlgorithmDatabase {
+    cases: Vec<AlgorithmCase>,
+}
+
+impl AlgorithmDatabase {
+    pub fn new() -> AlgorithmDatabase {
+        AlgorithmDatabase { cases: Vec::new() }
+    }
+
+    pub fn builtin() -> AlgorithmDatabase {
+        let mut db = AlgorithmDatabase::new();
+        db.load_str(BUILTIN_ALGORITHMS)
+            .expect("built-in algorithm database is invalid");
+        db
+    }
+
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read '{}': {}", path.display(), e))?;
+
+        self.load_str(&source)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    // the whole source is validated before anything is added, so a bad
+    // file leaves the database untouched
+    pub fn load_str(&mut self, source: &str) -> Result<(), String> {
+        let mut cases = self.cases.clone();
+        let mut current: Option<usize> = None;
+
+        for (line_no, line) in source.lines().enumerate() {
+            let line = line.trim();
+            let err = |e: String| format!("line {}: {}", line_no + 1, e);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if let Some(idx) = current {
+                    Self::check_complete(&cases[idx]).map_err(err)?;
+                }
+                let (set, name) = Self::parse_header(header).map_err(err)?;
+                current = Some(match cases.iter().position(|c| c.set == set && c.name == name) {
+                    Some(idx) => idx,
+                    None => {
+                        cases.push(AlgorithmCase {
+                            set,
+                            name,
+                            setup: Algorithm { notation: String::new(), moves: Vec::new() },
+                            algorithms: Vec::new(),
+                        });
+                        cases.len() - 1
+                    }
+                });
+                continue;
+            }
+
+            let idx = current.ok_or_else(|| err("expected a [<set> <name>] header".to_string()))?;
+            let case = &mut cases[idx];
+
+            if let Some(notation) = line.strip_prefix("setup:") {
+                if !case.setup.notation.is_empty() {
+                    return Err(err(format!("{} {} already has a setup", case.set.name(), case.name)));
+                }
+                let setup = Self::parse_entry(notation).map_err(err)?;
+                let grid = {
+                    let mut grid = Grid::new();
+                    grid.apply_moves(&setup.moves);
+                    grid
+                };
+                if !case.set.is_case(&grid) || case.set.is_goal(&grid) {
+                    return Err(err(format!("'{}' is not a {} case", setup.notation, case.set.name())));
+                }
+                case.setup = setup;
+            } else if let Some(notation) = line.strip_prefix("alg:") {
+                if case.setup.notation.is_empty() {
+                    return Err(err(format!("{} {} has no setup", case.set.name(), case.name)));
+                }
+                let algorithm = Self::parse_entry(notation).map_err(err)?;
+                case.validate(&algorithm).map_err(err)?;
+                case.algorithms.push(algorithm);
+            } else {
+                return Err(err(format!("unexpected line '{}'", line)));
+            }
+        }
+
+        if let Some(idx) = current {
+            Self::check_complete(&cases[idx])?;
+        }
+
+        self.cases = cases;
+        Ok(())
+    }
+
+    fn parse_header(header: &str) -> Result<(AlgorithmSet, String), String> {
+        let mut parts = header.trim().splitn(2, char::is_whitespace);
+        let set = AlgorithmSet::from_name(parts.next().unwrap_or(""))?;
+        let name = parts.next().unwrap_or("").trim();
+        if name.is_empty() {
+            return Err(format!("{} case without a name", set.name()));
+        }
+
+        Ok((set, name.to_string()))
+    }
+
+    fn parse_entry(notation: &str) -> Result<Algorithm, String> {
+        let notation = notation.trim();
+        let moves = parse_algorithm(notation)?;
+        if moves.is_empty() {
+            return Err("empty algorithm".to_string());
+        }
+
+        Ok(Algorithm { notation: notation.to_string(), moves })
+    }
+
+    fn check_complete(case: &AlgorithmCase) -> Result<(), String> {
+        if case.algorithms.is_empty() {
+            Err(format!("{} {} has no algorithms", case.set.name(), case.name))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn cases(&self) -> &[AlgorithmCase] {
+        &self.cases
+    }
+
+    pub fn cases_in(&self, set: AlgorithmSet) -> impl Iterator<Item = &AlgorithmCase> {
+        self.cases.iter().filter(move |c| c.set == set)
+    }
+
+    pub fn get(&self, set: AlgorithmSet, name: &str) -> Option<&AlgorithmCase> {
+        self.cases.iter()
+            .find(|c| c.set == set && c.name.eq_ignore_ascii_case(name))
+    }
+
+    // looks a case up by its full name, e.g. "PLL T" or "OLL 27"
+    pub fn find(&self, full_name: &str) -> Option<&AlgorithmCase> {
+        let (set, name) = Self::parse_header(full_name).ok()?;
+        self.get(set, &name)
+    }
+}
+
+fn center(grid: &Grid, side: GridSide) -> Color {
+    grid.faces[side.idx()].grid[1][1]
+}
+
+// the first two layers, counted from the bottom
+fn f2l(grid: &Grid) -> Goal {
+    Goal::F2l(center(grid, GridSide::Bottom))
+}
+
+fn are_top_edges_oriented(grid: &Grid) -> bool {
+    let top = &grid.faces[GridSide::Top.idx()].grid;
+    let color = center(grid, GridSide::Top);
+
+    [top[0][1], top[1][0], top[1][2], top[2][1]].iter().all(|&c| c == color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_database() {
+        let db = AlgorithmDatabase::builtin();
+
+        for set in [AlgorithmSet::Oll, AlgorithmSet::Pll, AlgorithmSet::F2l, AlgorithmSet::Ocll] {
+            assert!(db.cases_in(set).count() > 0);
+        }
+        // the complete sets
+        assert_eq!(db.cases_in(AlgorithmSet::Pll).count(), 21);
+        assert_eq!(db.cases_in(AlgorithmSet::Ocll).count(), 7);
+
+        let t_perm = db.find("PLL T").unwrap();
+        let mut grid = t_perm.setup_grid();
+        grid.apply_moves(&t_perm.algorithms[0].moves);
+        assert!(grid.is_solved());
+    }
+
+    #[test]
+    fn test_extending_database() {
+        let mut db = AlgorithmDatabase::builtin();
+        let sune_algs = db.get(AlgorithmSet::Oll, "27").unwrap().algorithms.len();
+
+        db.load_str("
+            # more sunes
+            [OLL 27]
+            alg: y' R' U2 R U R' U R y
+
+            [PLL Sledge]
+            setup: R U R' U' R' F R2 U' R' U' R U R' F'
+            alg: R U R' U' R' F R2 U' R' U' R U R' F'
+        ").unwrap();
+
+        assert_eq!(db.get(AlgorithmSet::Oll, "27").unwrap().algorithms.len(), sune_algs + 1);
+        assert!(db.find("pll sledge").is_some());
+    }
+
+    #[test]
+    fn test_rejecting_invalid_algorithms() {
+        let mut db = AlgorithmDatabase::builtin();
+        let cases = db.cases().len();
+
+        assert!(db.load_str("[PLL T]\nalg: R U R' U'").is_err());
+        assert!(db.load_str("[OLL New]\nsetup: R U R' U'\nalg: U R U' R'").is_err());
+        assert!(db.load_str("[PLL Nothing]\nalg: R U R' U'").is_err());
+        assert!(db.load_str("[ZBLL T]\nsetup: R U R' U'\nalg: U R U' R'").is_err());
+        assert_eq!(db.cases().len(), cases);
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::cube::core::{
+    algorithm::parse_algorithm,
+    grid::{Grid, GridSide, MoveDirection},
+    search::move_set,
+    sticker::{StickerGrid, StickerPos},
+    symmetry::ROTATIONS
+};
+
+// the center sitting on a face: which face it came from and by how many
+// clockwise quarter turns it is turned against the stickers of the face
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CenterOrientation {
+    pub home: GridSide,
+    pub turns: u8,
+}
+
+pub const SOLVED_CENTERS: [CenterOrientation; 6] = [
+    CenterOrientation { home: GridSide::Top, turns: 0 },
+    CenterOrientation { home: GridSide::Left, turns: 0 },
+    CenterOrientation { home: GridSide::Front, turns: 0 },
+    CenterOrientation { home: GridSide::Right, turns: 0 },
+    CenterOrientation { home: GridSide::Back, turns: 0 },
+    CenterOrientation { home: GridSide::Bottom, turns: 0 },
+];
+
+// centers after doing the moves behind `first` and then the ones behind `second`
+pub fn compose_centers(first: &[CenterOrientation; 6], second: &[CenterOrientation; 6]) -> [CenterOrientation; 6] {
+    std::array::from_fn(|idx| {
+        let moved = first[second[idx].home.idx()];
+        CenterOrientation { home: moved.home, turns: (moved.turns + second[idx].turns) % 4 }
+    })
+}
+
+pub fn invert_centers(centers: &[CenterOrientation; 6]) -> [CenterOrientation; 6] {
+    let mut inverse = SOLVED_CENTERS;
+    for (idx, center) in centers.iter().enumerate() {
+        inverse[center.home.idx()] = CenterOrientation {
+            home: GridSide::from_idx(idx),
+            turns: (4 - center.turns) % 4,
+        };
+    }
+    inverse
+}
+
+// the stickers on both sides of a center, across and along the rows
+const CENTER_LINES: [[(usize, usize); 2]; 2] = [
+    [(0, 1), (2, 1)],
+    [(1, 0), (1, 2)],
+];
+
+fn turn_clockwise((row, col): (isize, isize)) -> (isize, isize) {
+    (col, -row)
+}
+
+// how the center of `side` travels under a permutation of the stickers:
+// where it ends up and how much its face frame turns on the way, seen
+// from a line of stickers through the center moving along with it
+fn center_transport(stickers: &StickerGrid, side: usize) -> (usize, u8) {
+    let to = stickers.position_of(StickerPos::new(GridSide::from_idx(side), 1, 1).id()).side;
+
+    for [(r1, c1), (r2, c2)] in CENTER_LINES {
+        let a = stickers.position_of(StickerPos::new(GridSide::from_idx(side), r1, c1).id());
+        let b = stickers.position_of(StickerPos::new(GridSide::from_idx(side), r2, c2).id());
+        if a.side != to || b.side != to {
+            continue;
+        }
+
+        let before = (r2 as isize - r1 as isize, c2 as isize - c1 as isize);
+        let after = (b.row as isize - a.row as isize, b.col as isize - a.col as isize);
+
+        let mut turned = before;
+        for turns in 0..4 {
+            if turned == after {
+                return (to.idx(), turns);
+            }
+            turned = turn_clockwise(turned);
+        }
+    }
+    panic!("a line of stickers always moves along with the center")
+}
+
+// a center going from one face to another, turning on the way
+type CenterMove = (usize, usize, u8);
+
+// for every move: each center that moves or turns
+fn center_moves() -> &'static HashMap<(GridSide, MoveDirection), Vec<CenterMove>> {
+    static CENTER_MOVES: OnceLock<HashMap<(GridSide, MoveDirection), Vec<CenterMove>>> = OnceLock::new();
+
+    CENTER_MOVES.get_or_init(|| {
+        use GridSide::*;
+
+        move_set(&[Top, Bottom, Left, Right, Front, Back, MiddleX, MiddleY, MiddleZ]).into_iter()
+            .map(|mv| {
+                let mut stickers = StickerGrid::identity();
+                stickers.permute(mv.grid_side, mv.direction);
+
+                let moved = (0..6)
+                    .map(|from| {
+                        let (to, turns) = center_transport(&stickers, from);
+                        (from, to, turns)
+                    })
+                    .filter(|&(from, to, turns)| from != to || turns != 0)
+                    .collect();
+                ((mv.grid_side, mv.direction), moved)
+            })
+            .collect()
+    })
+}
+
+// centers of a solved cube held in each of the 24 orientations
+fn rotated_centers() -> &'static Vec<[CenterOrientation; 6]> {
+    static ROTATED_CENTERS: OnceLock<Vec<[CenterOrientation; 6]>> = OnceLock::new();
+
+    ROTATED_CENTERS.get_or_init(|| {
+        ROTATIONS.iter()
+            .map(|rotation| StickerGrid::from_moves(&parse_algorithm(rotation).unwrap()).centers)
+            .collect()
+    })
+}
+
+impl<T: Copy> Grid<T> {
+    pub(crate) fn turn_centers(&mut self, side: GridSide, direction: MoveDirection) {
+        let before = self.centers;
+        for &(from, to, turns) in &center_moves()[&(side, direction)] {
+            self.centers[to] = CenterOrientation {
+                home: before[from].home,
+                turns: (before[from].turns + turns) % 4,
+            };
+        }
+    }
+
+    // whether the centers are turned the way they are on a solved cube,
+    // no matter how the whole cube is held
+    pub fn are_centers_oriented(&self) -> bool {
+        rotated_centers().contains(&self.centers)
+    }
+}
+
+impl Grid {
+    // solved like a supercube, where the centers have to face the right way
+    pub fn is_solved_with_centers(&self) -> bool {
+        self.is_solved() && self.are_centers_oriented()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_face_turns_turn_centers() {
+        let grid = Grid::after("U");
+        assert_eq!(grid.centers[GridSide::Top.idx()].turns, 1);
+        assert!(!grid.are_centers_oriented());
+
+        let grid = Grid::after("U2 D'");
+        assert_eq!(grid.centers[GridSide::Top.idx()].turns, 2);
+        assert_eq!(grid.centers[GridSide::Bottom.idx()].turns, 3);
+
+        let grid = Grid::after(&"R U R' U' ".repeat(6));
+        assert!(grid.is_solved_with_centers());
+
+        // every sticker is back in place, but the top center is turned half way around
+        let grid = Grid::after("U R L U2 R' L' U R L U2 R' L'");
+        assert!(grid.is_solved());
+        assert_eq!(grid.centers[GridSide::Top.idx()].turns, 2);
+        assert!(!grid.is_solved_with_centers());
+    }
+
+    #[test]
+    fn test_rotations_keep_centers_oriented() {
+        for rotation in ROTATIONS {
+            let grid = Grid::after(rotation);
+            assert!(grid.is_solved_with_centers(), "{}", rotation);
+        }
+
+        // a twisted center is still noticed when the cube is held differently
+        let grid = Grid::after("z y (U R L U2 R' L') (U R L U2 R' L')");
+        assert!(grid.is_solved());
+        assert!(!grid.are_centers_oriented());
+    }
+
+    #[test]
+    fn test_centers_follow_slices() {
+        let grid = Grid::after("M");
+        assert_eq!(grid.centers[GridSide::Front.idx()].home, GridSide::Top);
+        assert_eq!(grid.centers[GridSide::Bottom.idx()].home, GridSide::Front);
+
+        let moves = parse_algorithm("R U2 M' E S2 F' x y'").unwrap();
+        let stickers = StickerGrid::from_moves(&moves);
+        let inverse = stickers.inverse();
+        assert_eq!(stickers.compose(&inverse).centers, SOLVED_CENTERS);
+        assert_eq!(inverse.compose(&stickers).centers, SOLVED_CENTERS);
+    }
+}
@@ -1,16 +1,18 @@
 use crate::{
     utils::{
         cube_utils::{Axis, Color},
+        color_scheme::ColorScheme,
     },
     cube::{
-        slice::{CubeMove, CubeSliceOrder}
+        slice::{CubeMove, CubeSliceOrder},
+        core::center::{CenterOrientation, SOLVED_CENTERS}
     }
 };
 
 const PRINT_CHAR: &str = "██";
 const ANSI_RESET: &str = "\x1b[0m";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MoveDirection {
     Clockwise,
     CounterClockwise,
@@ -137,7 +139,7 @@ pub struct NeighborSlice {
 }
 
 impl NeighborSlice {
-    pub fn read_from(&self, grid: &Grid) -> [Color; 3] {
+    pub fn read_from<T: Copy>(&self, grid: &Grid<T>) -> [T; 3] {
         let face = &grid.faces[self.side.idx()];
         match self.slice_type {
             SliceType::Top => {
@@ -199,7 +201,7 @@ impl NeighborSlice {
         }
     }
 
-    fn write_to(&self, grid: &mut Grid, colors: [Color; 3]) {
+    fn write_to<T: Copy>(&self, grid: &mut Grid<T>, colors: [T; 3]) {
         let face = &mut grid.faces[self.side.idx()];
         match self.slice_type {
             SliceType::Top => {
@@ -247,8 +249,8 @@ impl NeighborSlice {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct GridFace {
-    pub grid: [[Color; 3]; 3],
+pub struct GridFace<T = Color> {
+    pub grid: [[T; 3]; 3],
 }
 
 impl GridFace {
@@ -288,7 +290,9 @@ impl GridFace {
             print!("{}{}{}", color.to_ansi(), PRINT_CHAR, ANSI_RESET);
         }
     }
+}
 
+impl<T: Copy> GridFace<T> {
     pub fn rotate(&mut self, direction: &MoveDirection) {
         match direction {
             MoveDirection::Clockwise => self.rotate_clockwise(),
@@ -333,21 +337,22 @@ impl GridFace {
     }
 }
 
-pub struct Grid {
-    pub faces: [GridFace; 6],
+#[derive(Clone)]
+pub struct Grid<T = Color> {
+    pub faces: [GridFace<T>; 6],
+    pub centers: [CenterOrientation; 6],
 }
 
 impl Grid {
     pub fn new() -> Grid {
+        Self::with_scheme(&ColorScheme::default())
+    }
+
+    // solved cube colored by the scheme
+    pub fn with_scheme(scheme: &ColorScheme) -> Grid {
         Grid {
-            faces: [
-                GridFace::new(Color::White),
-                GridFace::new(Color::Orange),
-                GridFace::new(Color::Green),
-                GridFace::new(Color::Red),
-                GridFace::new(Color::Blue),
-                GridFace::new(Color::Yellow),
-            ]
+            faces: scheme.faces.map(GridFace::new),
+            centers: SOLVED_CENTERS,
         }
     }
 
@@ -360,10 +365,6 @@ impl Grid {
         true
     }
 
-    pub fn apply_move(&mut self, mv: CubeMove) {
-        self.move_face(mv.grid_side, mv.direction);
-    }
-
     pub fn print(&self) {
         fn print_blank_row() {
             for _ in 0..3 {
@@ -396,8 +397,20 @@ impl Grid {
 
         print!("\n\n\n");
     }
+}
+
+impl<T: Copy> Grid<T> {
+    pub fn apply_move(&mut self, mv: CubeMove) {
+        self.move_face(mv.grid_side, mv.direction);
+    }
+
+    pub fn apply_moves(&mut self, moves: &[CubeMove]) {
+        for mv in moves {
+            self.move_face(mv.grid_side, mv.direction);
+        }
+    }
 
-    fn rotate_buffers(buffers: &mut Vec<[Color; 3]>, grid_side: &GridSide, direction: MoveDirection) {
+    fn rotate_buffers(buffers: &mut Vec<[T; 3]>, grid_side: &GridSide, direction: MoveDirection) {
         match grid_side {
             GridSide::Left 
                 | GridSide::Top
@@ -418,13 +431,19 @@ impl Grid {
     }
 
     pub fn move_face(&mut self, side: GridSide, direction: MoveDirection) {
+        self.permute(side, direction);
+        self.turn_centers(side, direction);
+    }
+
+    // moves the stickers only, leaving the orientation of the centers be
+    pub(crate) fn permute(&mut self, side: GridSide, direction: MoveDirection) {
         if !side.is_middle() {
             let idx = side.idx();
             self.faces[idx].rotate(&direction);
         }
 
         let neighbors = self.get_neighbors(side);
-        let mut buffers: Vec<[Color; 3]> = neighbors.iter()
+        let mut buffers: Vec<[T; 3]> = neighbors.iter()
             .map(|ns| {
                     ns.read_from(self)
                 }
@@ -498,6 +517,16 @@ impl Grid {
     }
 }
 
+#[cfg(test)]
+impl Grid {
+    // a solved grid after the moves, to set positions up in tests
+    pub(crate) fn after(notation: &str) -> Grid {
+        let mut grid = Grid::new();
+        grid.apply_moves(&crate::cube::core::algorithm::parse_algorithm(notation).unwrap());
+        grid
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -559,6 +588,7 @@ mod tests {
                 GridFace::new(Blue),    // BACK
                 GridFace::new(Yellow),  // BOTTOM
             ],
+            centers: SOLVED_CENTERS,
         }
     }
 
@@ -602,6 +632,7 @@ mod tests {
                     [Orange, Blue, Red],
                 ]),
             ],
+            centers: SOLVED_CENTERS,
         }
     }
 
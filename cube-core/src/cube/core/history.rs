@@ -0,0 +1,219 @@
+use crate::cube::{
+    slice::CubeMove,
+    core::{
+        algorithm::algorithm_to_string,
+        grid::Grid
+    }
+};
+
+struct HistoryNode {
+    parent: Option<usize>,
+    // the move leading here from the parent, none for the starting position
+    mv: Option<CubeMove>,
+    children: Vec<usize>,
+    // the child redo goes to, the one visited last
+    redo: Option<usize>,
+}
+
+// a grid remembering every move done on it; undoing and then doing
+// a different move starts a new branch, keeping the old one for later
+pub struct MoveHistory {
+    grid: Grid,
+    nodes: Vec<HistoryNode>,
+    current: usize,
+}
+
+impl MoveHistory {
+    pub fn new(grid: Grid) -> MoveHistory {
+        MoveHistory {
+            grid,
+            nodes: vec![HistoryNode { parent: None, mv: None, children: Vec::new(), redo: None }],
+            current: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    // forgets the history, starting again from the grid
+    pub fn reset(&mut self, grid: Grid) {
+        *self = MoveHistory::new(grid);
+    }
+
+    pub fn apply_move(&mut self, mv: CubeMove) {
+        self.grid.apply_move(mv.clone());
+
+        // doing the move of an existing branch again follows that branch
+        let existing = self.nodes[self.current].children.iter()
+            .copied()
+            .find(|&child| self.nodes[child].mv.as_ref() == Some(&mv));
+        let idx = existing.unwrap_or_else(|| {
+            let idx = self.nodes.len();
+            self.nodes.push(HistoryNode { parent: Some(self.current), mv: Some(mv), children: Vec::new(), redo: None });
+            self.nodes[self.current].children.push(idx);
+            idx
+        });
+
+        self.nodes[self.current].redo = Some(idx);
+        self.current = idx;
+    }
+
+    pub fn apply_moves(&mut self, moves: &[CubeMove]) {
+        for mv in moves {
+            self.apply_move(mv.clone());
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.nodes[self.current].parent.is_some()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.nodes[self.current].redo.is_some()
+    }
+
+    // steps back one move and returns the move done to the grid for that,
+    // which is the inverse of the undone one
+    pub fn undo(&mut self) -> Option<CubeMove> {
+        let node = &self.nodes[self.current];
+        let parent = node.parent?;
+        let inverse = node.mv.as_ref()?.inverse();
+
+        self.grid.apply_move(inverse.clone());
+        self.nodes[parent].redo = Some(self.current);
+        self.current = parent;
+
+        Some(inverse)
+    }
+
+    // does the last undone move again, on the branch visited last
+    pub fn redo(&mut self) -> Option<CubeMove> {
+        let child = self.nodes[self.current].redo?;
+        self.enter(child)
+    }
+
+    // moves starting a branch from the current position, oldest first
+    pub fn branches(&self) -> Vec<CubeMove> {
+        self.nodes[self.current].children.iter()
+            .filter_map(|&child| self.nodes[child].mv.clone())
+            .collect()
+    }
+
+    // redoes the move of the given branch from `branches`
+    pub fn redo_branch(&mut self, branch: usize) -> Option<CubeMove> {
+        let child = *self.nodes[self.current].children.get(branch)?;
+        self.nodes[self.current].redo = Some(child);
+        self.enter(child)
+    }
+
+    fn enter(&mut self, child: usize) -> Option<CubeMove> {
+        let mv = self.nodes[child].mv.clone()?;
+        self.grid.apply_move(mv.clone());
+        self.current = child;
+        Some(mv)
+    }
+
+    // moves leading from the start to the current position
+    pub fn moves(&self) -> Vec<CubeMove> {
+        let mut moves = Vec::new();
+        let mut idx = self.current;
+        while let Some(parent) = self.nodes[idx].parent {
+            moves.extend(self.nodes[idx].mv.clone());
+            idx = parent;
+        }
+        moves.reverse();
+        moves
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.can_undo()
+    }
+
+    pub fn to_notation(&self) -> String {
+        algorithm_to_string(&self.moves())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::algorithm::parse_algorithm;
+
+    fn history(notation: &str) -> MoveHistory {
+        let mut history = MoveHistory::new(Grid::new());
+        history.apply_moves(&parse_algorithm(notation).unwrap());
+        history
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut history = history("R U R'");
+        assert_eq!(history.to_notation(), "R U R'");
+        assert!(!history.can_redo());
+
+        assert_eq!(history.undo().unwrap().to_notation(), "R");
+        assert_eq!(history.undo().unwrap().to_notation(), "U'");
+        assert_eq!(history.to_notation(), "R");
+        assert_eq!(history.redo().unwrap().to_notation(), "U");
+        assert_eq!(history.to_notation(), "R U");
+
+        while history.undo().is_some() {}
+        assert!(history.is_empty());
+        assert!(history.grid().is_solved());
+
+        while history.redo().is_some() {}
+        assert_eq!(history.to_notation(), "R U R'");
+        assert_eq!(history.grid().faces, {
+            let mut grid = Grid::new();
+            grid.apply_moves(&parse_algorithm("R U R'").unwrap());
+            grid
+        }.faces);
+    }
+
+    #[test]
+    fn test_branches() {
+        let mut history = history("R U");
+        history.undo();
+        history.apply_moves(&parse_algorithm("F D").unwrap());
+        assert_eq!(history.to_notation(), "R F D");
+
+        history.undo();
+        history.undo();
+        assert_eq!(history.branches().iter().map(|mv| mv.to_notation()).collect::<Vec<_>>(), vec!["U", "F"]);
+
+        // redo follows the branch visited last
+        history.redo();
+        assert_eq!(history.to_notation(), "R F");
+        history.undo();
+
+        history.redo_branch(0);
+        assert_eq!(history.to_notation(), "R U");
+        assert!(!history.can_redo());
+        history.undo();
+        history.redo();
+        assert_eq!(history.to_notation(), "R U");
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn test_same_move_reuses_branch() {
+        let mut history = history("R U R'");
+        history.undo();
+        history.undo();
+        history.apply_moves(&parse_algorithm("U R'").unwrap());
+        assert_eq!(history.to_notation(), "R U R'");
+        assert_eq!(history.nodes.len(), 4);
+
+        history.undo();
+        history.undo();
+        assert_eq!(history.branches().len(), 1);
+        history.apply_moves(&parse_algorithm("U'").unwrap());
+        history.undo();
+        assert_eq!(history.branches().iter().map(|mv| mv.to_notation()).collect::<Vec<_>>(), vec!["U", "U'"]);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    utils::{
+        cube_utils::Color,
+    },
+    cube::{
+        core::{
+            grid::{Grid, GridSide},
+            piece::Piece,
+            sticker::StickerPos
+        }
+    }
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskPreset {
+    // only the pieces of the top layer
+    LastLayer,
+    // the centers, the cross on the bottom and the front right pair
+    CrossAndPair,
+    // only the stickers of the top color
+    Oll,
+}
+
+impl MaskPreset {
+    pub fn from_name(name: &str) -> Option<MaskPreset> {
+        match name.to_lowercase().as_str() {
+            "ll" | "last-layer" => Some(MaskPreset::LastLayer),
+            "cross-and-pair" => Some(MaskPreset::CrossAndPair),
+            "oll" => Some(MaskPreset::Oll),
+            _ => None,
+        }
+    }
+
+    // whether a sticker of the given color stays visible on a piece
+    // with the given colors
+    fn is_visible(&self, color: Color, piece: &Piece, colors: &[Color], centers: &[Color; 6]) -> bool {
+        let center = |side: GridSide| centers[side.idx()];
+
+        match self {
+            MaskPreset::LastLayer => colors.contains(&center(GridSide::Top)),
+            MaskPreset::CrossAndPair => match piece {
+                Piece::Center(_) => true,
+                Piece::Edge(_) => {
+                    colors.contains(&center(GridSide::Bottom))
+                        || [GridSide::Front, GridSide::Right].iter().all(|&side| colors.contains(&center(side)))
+                }
+                Piece::Corner(_) => {
+                    [GridSide::Bottom, GridSide::Front, GridSide::Right].iter().all(|&side| colors.contains(&center(side)))
+                }
+            },
+            MaskPreset::Oll => color == center(GridSide::Top),
+        }
+    }
+}
+
+impl Grid {
+    pub fn is_masked(&self, pos: StickerPos) -> bool {
+        self.at(pos) == Color::Gray
+    }
+
+    pub fn mask(&mut self, positions: &[StickerPos]) {
+        for &pos in positions {
+            self.set(pos, Color::Gray);
+        }
+    }
+
+    // the grid with everything the preset hides turned gray; pieces are
+    // recognized by their colors, so the mask follows them wherever they are
+    pub fn masked(&self, preset: MaskPreset) -> Grid {
+        let centers: [Color; 6] = std::array::from_fn(|idx| self.faces[idx].grid[1][1]);
+        let mut masked = self.clone();
+
+        for piece in Piece::all() {
+            let stickers = piece.stickers();
+            let colors: Vec<Color> = stickers.iter().map(|&pos| self.at(pos)).collect();
+
+            for (&pos, &color) in stickers.iter().zip(colors.iter()) {
+                if !preset.is_visible(color, &piece, &colors, &centers) {
+                    masked.set(pos, Color::Gray);
+                }
+            }
+        }
+        masked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::{core::algorithm::parse_algorithm, cube::Cube};
+
+    fn visible(grid: &Grid) -> usize {
+        StickerPos::all().filter(|&pos| !grid.is_masked(pos)).count()
+    }
+
+    #[test]
+    fn test_presets() {
+        let grid = Grid::new();
+
+        assert_eq!(visible(&grid.masked(MaskPreset::LastLayer)), 21);
+        assert_eq!(visible(&grid.masked(MaskPreset::Oll)), 9);
+        // 6 centers, 4 cross edges, the pair edge and corner
+        assert_eq!(visible(&grid.masked(MaskPreset::CrossAndPair)), 6 + 8 + 2 + 3);
+    }
+
+    #[test]
+    fn test_mask_follows_pieces() {
+        let moves = parse_algorithm("R U R' U' F2 D").unwrap();
+
+        let mut masked = Grid::new().masked(MaskPreset::LastLayer);
+        masked.apply_moves(&moves);
+
+        let mut grid = Grid::new();
+        grid.apply_moves(&moves);
+
+        assert_eq!(masked.faces, grid.masked(MaskPreset::LastLayer).faces);
+    }
+
+    #[test]
+    fn test_mask_is_only_shown() {
+        let mut cube = Cube::new((0.0, 0.0, 5.0), 0.0, 0.0);
+        cube.set_mask(Some(MaskPreset::Oll));
+
+        // the grid keeps its colors, so a scrambled cube is never solved
+        let mut grid = Grid::new();
+        grid.apply_moves(&parse_algorithm("U D2 E").unwrap());
+        assert!(!grid.is_solved());
+
+        cube.apply_grid(&grid);
+        let shown: Vec<Color> = cube.faces.iter().flat_map(|face| face.grid_face.grid.into_iter().flatten()).collect();
+        assert_eq!(shown.iter().filter(|&&color| color != Color::Gray).count(), 9);
+        assert_eq!(cube.shown(&grid).faces, grid.masked(MaskPreset::Oll).faces);
+    }
+}
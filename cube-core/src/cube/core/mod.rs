@@ -1,2 +1,13 @@
 pub mod grid;
-pub mod scramble;
\ No newline at end of file
+pub mod scramble;
+pub mod algorithm;
+pub mod algorithm_db;
+pub mod search;
+pub mod sticker;
+pub mod piece;
+pub mod symmetry;
+pub mod mask;
+pub mod center;
+pub mod predicate;
+pub mod history;
+pub mod orientation;
@@ -0,0 +1,173 @@
+use std::sync::OnceLock;
+
+use crate::cube::{
+    slice::CubeMove,
+    core::{
+        algorithm::parse_algorithm,
+        grid::{GridSide, MoveDirection},
+        sticker::StickerGrid,
+        symmetry::ROTATIONS
+    }
+};
+
+// how the whole cube is held, one of the 24 rotations from holding it
+// straight; moves typed for the sides as seen get done on the faces there
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    rotation: usize,
+}
+
+impl Orientation {
+    pub fn identity() -> Orientation {
+        Orientation { rotation: 0 }
+    }
+
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        (0..ROTATIONS.len()).map(|rotation| Orientation { rotation })
+    }
+
+    // the rotation in standard notation that turns a cube held straight into this
+    pub fn rotation(&self) -> &'static str {
+        ROTATIONS[self.rotation]
+    }
+
+    // the orientation with the given faces on the front and on top, if they are adjacent
+    pub fn from_sides(front: GridSide, top: GridSide) -> Option<Orientation> {
+        Orientation::all().find(|o| o.facing(GridSide::Front) == front && o.facing(GridSide::Top) == top)
+    }
+
+    // face of the cube seen on the side
+    pub fn facing(&self, side: GridSide) -> GridSide {
+        if side.is_middle() {
+            panic!("only outer faces are seen");
+        }
+        sides()[self.rotation][side.idx()]
+    }
+
+    // the cube turned as a whole the way the side turns, like `x` for the right side
+    pub fn turn(&self, side: GridSide, direction: MoveDirection) -> Orientation {
+        let (axis, inverted) = match side {
+            GridSide::Right => ("x", false),
+            GridSide::Left => ("x", true),
+            GridSide::Top => ("y", false),
+            GridSide::Bottom => ("y", true),
+            GridSide::Front => ("z", false),
+            GridSide::Back => ("z", true),
+            _ => panic!("the cube turns around an outer face"),
+        };
+        let suffix = match (direction, inverted) {
+            (MoveDirection::Double, _) => "2",
+            (MoveDirection::Clockwise, false) | (MoveDirection::CounterClockwise, true) => "",
+            _ => "'",
+        };
+
+        let moves = parse_algorithm(&format!("{} {}{}", self.rotation(), axis, suffix)).unwrap();
+        let turned = homes(&StickerGrid::from_moves(&moves));
+        Orientation::all()
+            .find(|o| sides()[o.rotation] == turned)
+            .expect("turning a held cube gives another way of holding it")
+    }
+
+    // the move on the grid for a move typed as the cube is seen
+    pub fn translate_move(&self, cube_move: CubeMove) -> CubeMove {
+        let side = cube_move.grid_side;
+        let mut direction = cube_move.direction;
+        let translated = self.facing(side.middle_layer_adjacent());
+        if side.is_middle() {
+            let translated_middle = GridSide::middle_layer_from_axis(&translated.axis());
+            if translated.idx() != translated_middle.middle_layer_adjacent().idx() {
+                direction = direction.flip();
+            }
+            CubeMove::from_side(translated_middle, direction)
+        } else {
+            CubeMove::from_side(translated, direction)
+        }
+    }
+}
+
+fn homes(stickers: &StickerGrid) -> [GridSide; 6] {
+    stickers.centers.map(|center| center.home)
+}
+
+// for every rotation: the face seen on each side
+fn sides() -> &'static Vec<[GridSide; 6]> {
+    static SIDES: OnceLock<Vec<[GridSide; 6]>> = OnceLock::new();
+
+    SIDES.get_or_init(|| {
+        ROTATIONS.iter()
+            .map(|rotation| homes(&StickerGrid::from_moves(&parse_algorithm(rotation).unwrap())))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orientation(rotation: &str) -> Orientation {
+        Orientation::all().find(|o| o.rotation() == rotation).unwrap()
+    }
+
+    fn translate(o: Orientation, notation: &str) -> String {
+        let mv = parse_algorithm(notation).unwrap().remove(0);
+        o.translate_move(mv).to_notation()
+    }
+
+    #[test]
+    fn test_facing() {
+        let straight = Orientation::identity();
+        for side in (0..6).map(GridSide::from_idx) {
+            assert_eq!(straight.facing(side), side);
+        }
+
+        let o = orientation("x");
+        assert_eq!(o.facing(GridSide::Front), GridSide::Bottom);
+        assert_eq!(o.facing(GridSide::Top), GridSide::Front);
+        assert_eq!(o.facing(GridSide::Right), GridSide::Right);
+        assert_eq!(Orientation::from_sides(GridSide::Bottom, GridSide::Front), Some(o));
+        assert_eq!(Orientation::from_sides(GridSide::Top, GridSide::Bottom), None);
+
+        // each orientation shows every face once
+        for o in Orientation::all() {
+            let mut seen: Vec<GridSide> = (0..6).map(|idx| o.facing(GridSide::from_idx(idx))).collect();
+            seen.sort_by_key(|side| side.idx());
+            assert_eq!(seen, (0..6).map(GridSide::from_idx).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_turns() {
+        let straight = Orientation::identity();
+        assert_eq!(straight.turn(GridSide::Right, MoveDirection::Clockwise), orientation("x"));
+        assert_eq!(straight.turn(GridSide::Left, MoveDirection::Clockwise), orientation("x'"));
+        assert_eq!(straight.turn(GridSide::Top, MoveDirection::Double), orientation("y2"));
+
+        let mut o = orientation("z y");
+        for _ in 0..4 {
+            o = o.turn(GridSide::Front, MoveDirection::CounterClockwise);
+        }
+        assert_eq!(o, orientation("z y"));
+    }
+
+    #[test]
+    fn test_translate_move() {
+        let o = orientation("y");
+        assert_eq!(translate(o, "F"), "R");
+        assert_eq!(translate(o, "R'"), "B'");
+        assert_eq!(translate(o, "U2"), "U2");
+        assert_eq!(translate(o, "M"), "S");
+        assert_eq!(translate(Orientation::identity(), "E'"), "E'");
+
+        // a move typed on a turned cube is the move seen after the rotation
+        for o in Orientation::all() {
+            for notation in ["R", "U'", "F2", "M", "E'", "S"] {
+                let mut typed = StickerGrid::from_moves(&parse_algorithm(o.rotation()).unwrap());
+                typed = typed.compose(&StickerGrid::from_moves(&parse_algorithm(notation).unwrap()));
+
+                let mut done = StickerGrid::from_moves(&parse_algorithm(&translate(o, notation)).unwrap());
+                done = done.compose(&StickerGrid::from_moves(&parse_algorithm(o.rotation()).unwrap()));
+                assert_eq!(typed.faces, done.faces, "{} {}", o.rotation(), notation);
+            }
+        }
+    }
+}
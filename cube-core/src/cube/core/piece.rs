@@ -0,0 +1,223 @@
+use crate::{
+    utils::{
+        cube_utils::Color,
+    },
+    cube::{
+        core::{
+            grid::{Grid, GridSide},
+            sticker::{StickerGrid, StickerId, StickerPos}
+        }
+    }
+};
+
+use GridSide::*;
+
+type Facelet = (GridSide, usize, usize);
+
+// stickers of every corner, starting with the top or bottom one
+// and going clockwise around the corner
+const CORNERS: [(&str, [Facelet; 3]); 8] = [
+    ("URF", [(Top, 2, 2), (Right, 0, 0), (Front, 0, 2)]),
+    ("UFL", [(Top, 2, 0), (Front, 0, 0), (Left, 0, 2)]),
+    ("ULB", [(Top, 0, 0), (Left, 0, 0), (Back, 0, 2)]),
+    ("UBR", [(Top, 0, 2), (Back, 0, 0), (Right, 0, 2)]),
+    ("DFR", [(Bottom, 0, 2), (Front, 2, 2), (Right, 2, 0)]),
+    ("DLF", [(Bottom, 0, 0), (Left, 2, 2), (Front, 2, 0)]),
+    ("DBL", [(Bottom, 2, 0), (Back, 2, 2), (Left, 2, 0)]),
+    ("DRB", [(Bottom, 2, 2), (Right, 2, 2), (Back, 2, 0)]),
+];
+
+const EDGES: [(&str, [Facelet; 2]); 12] = [
+    ("UR", [(Top, 1, 2), (Right, 0, 1)]),
+    ("UF", [(Top, 2, 1), (Front, 0, 1)]),
+    ("UL", [(Top, 1, 0), (Left, 0, 1)]),
+    ("UB", [(Top, 0, 1), (Back, 0, 1)]),
+    ("DR", [(Bottom, 1, 2), (Right, 2, 1)]),
+    ("DF", [(Bottom, 0, 1), (Front, 2, 1)]),
+    ("DL", [(Bottom, 1, 0), (Left, 2, 1)]),
+    ("DB", [(Bottom, 2, 1), (Back, 2, 1)]),
+    ("FR", [(Front, 1, 2), (Right, 1, 0)]),
+    ("FL", [(Front, 1, 0), (Left, 1, 2)]),
+    ("BL", [(Back, 1, 2), (Left, 1, 0)]),
+    ("BR", [(Back, 1, 0), (Right, 1, 2)]),
+];
+
+const CENTERS: [(&str, GridSide); 6] = [
+    ("U", Top),
+    ("L", Left),
+    ("F", Front),
+    ("R", Right),
+    ("B", Back),
+    ("D", Bottom),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Piece {
+    Center(usize),
+    Edge(usize),
+    Corner(usize),
+}
+
+impl Piece {
+    pub fn all() -> impl Iterator<Item = Piece> {
+        (0..CENTERS.len()).map(Piece::Center)
+            .chain((0..EDGES.len()).map(Piece::Edge))
+            .chain((0..CORNERS.len()).map(Piece::Corner))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Piece::Center(idx) => CENTERS[idx].0,
+            Piece::Edge(idx) => EDGES[idx].0,
+            Piece::Corner(idx) => CORNERS[idx].0,
+        }
+    }
+
+    pub fn stickers(&self) -> Vec<StickerPos> {
+        let to_pos = |&(side, row, col): &Facelet| StickerPos::new(side, row, col);
+        match *self {
+            Piece::Center(idx) => vec![StickerPos::new(CENTERS[idx].1, 1, 1)],
+            Piece::Edge(idx) => EDGES[idx].1.iter().map(to_pos).collect(),
+            Piece::Corner(idx) => CORNERS[idx].1.iter().map(to_pos).collect(),
+        }
+    }
+
+    // the piece whose slot holds the given sticker
+    pub fn containing(pos: StickerPos) -> Piece {
+        Piece::all()
+            .find(|piece| piece.stickers().contains(&pos))
+            .expect("every sticker belongs to a piece")
+    }
+
+    fn is_same_kind(&self, other: &Piece) -> bool {
+        matches!(
+            (self, other),
+            (Piece::Center(_), Piece::Center(_))
+                | (Piece::Edge(_), Piece::Edge(_))
+                | (Piece::Corner(_), Piece::Corner(_))
+        )
+    }
+
+    fn colors(&self, grid: &Grid) -> Vec<Color> {
+        self.stickers().into_iter()
+            .map(|pos| grid.at(pos))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceDifference {
+    // the slot holds a different piece
+    Misplaced,
+    // the slot holds the right piece, twisted or flipped
+    Misoriented,
+}
+
+// by how many stickers `colors` is turned against `home`, if it is the same piece
+fn orientation(colors: &[Color], home: &[Color]) -> Option<usize> {
+    (0..home.len()).find(|&twist| {
+        (0..home.len()).all(|i| colors[i] == home[(i + twist) % home.len()])
+    })
+}
+
+impl Grid {
+    // pieces that sit differently than on `target`
+    pub fn difference(&self, target: &Grid) -> Vec<(Piece, PieceDifference)> {
+        Piece::all()
+            .filter_map(|piece| {
+                let colors = piece.colors(self);
+                let target_colors = piece.colors(target);
+                match orientation(&colors, &target_colors) {
+                    Some(0) => None,
+                    Some(_) => Some((piece, PieceDifference::Misoriented)),
+                    None => Some((piece, PieceDifference::Misplaced)),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Grid<StickerId> {
+    // recovers which sticker is where by recognizing the pieces by their
+    // colors as they are on `solved`
+    pub fn from_colors(grid: &Grid, solved: &Grid) -> Result<StickerGrid, String> {
+        let mut stickers = StickerGrid::identity();
+        let mut used = Vec::new();
+
+        for slot in Piece::all() {
+            let colors = slot.colors(grid);
+            let (home, twist) = Piece::all()
+                .filter(|home| home.is_same_kind(&slot))
+                .filter(|home| !used.contains(home))
+                .find_map(|home| orientation(&colors, &home.colors(solved)).map(|twist| (home, twist)))
+                .ok_or_else(|| format!("No piece fits the {} slot", slot.name()))?;
+
+            let home_stickers = home.stickers();
+            for (i, pos) in slot.stickers().into_iter().enumerate() {
+                stickers.set(pos, home_stickers[(i + twist) % home_stickers.len()].id());
+            }
+            used.push(home);
+        }
+
+        Ok(stickers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::{
+        algorithm::parse_algorithm,
+        search::move_set,
+    };
+
+    #[test]
+    fn test_pieces_move_as_a_whole() {
+        let sides = [Top, Bottom, Left, Right, Front, Back, MiddleX, MiddleY, MiddleZ];
+        for mv in move_set(&sides) {
+            let mut stickers = StickerGrid::identity();
+            stickers.apply_move(mv);
+
+            for slot in Piece::all() {
+                let ids: Vec<StickerId> = slot.stickers().into_iter().map(|pos| stickers.at(pos)).collect();
+                let home = Piece::containing(StickerPos::from_id(ids[0]));
+                let home_ids: Vec<StickerId> = home.stickers().into_iter().map(|pos| pos.id()).collect();
+
+                let start = home_ids.iter().position(|&id| id == ids[0]).unwrap();
+                for (i, &id) in ids.iter().enumerate() {
+                    assert_eq!(id, home_ids[(start + i) % home_ids.len()]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_colors() {
+        let moves = parse_algorithm("R U2 F' L D B2 M E2 S' x y'").unwrap();
+        let mut grid = Grid::new();
+        grid.apply_moves(&moves);
+
+        let stickers = StickerGrid::from_colors(&grid, &Grid::new()).unwrap();
+        assert_eq!(stickers.faces, StickerGrid::from_moves(&moves).faces);
+
+        grid.faces[0].grid[0][0] = grid.faces[0].grid[0][1];
+        assert!(StickerGrid::from_colors(&grid, &Grid::new()).is_err());
+    }
+
+    #[test]
+    fn test_difference() {
+        let mut grid = Grid::new();
+        assert!(grid.difference(&Grid::new()).is_empty());
+
+        grid.apply_moves(&parse_algorithm("U").unwrap());
+        let difference = grid.difference(&Grid::new());
+        assert_eq!(difference.len(), 8);
+        assert!(difference.iter().all(|&(_, d)| d == PieceDifference::Misplaced));
+
+        let mut grid = Grid::new();
+        grid.apply_moves(&parse_algorithm("R' D' R D R' D' R D").unwrap());
+        let difference = grid.difference(&Grid::new());
+        assert!(difference.contains(&(Piece::Corner(0), PieceDifference::Misoriented)));
+        assert!(difference.iter().all(|(piece, _)| !piece.name().starts_with('U') || piece.name() == "URF"));
+    }
+}
@@ -0,0 +1,317 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::{
+    utils::{
+        cube_utils::Color,
+    },
+    cube::{
+        core::{
+            algorithm::parse_algorithm,
+            grid::{Grid, GridSide, MoveDirection},
+            piece::Piece,
+            symmetry::ROTATIONS
+        }
+    }
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Goal {
+    // every face of one color
+    Solved,
+    // the stickers of the face all match its center
+    FaceSolved(GridSide),
+    // every piece of the layer is in place
+    LayerSolved(GridSide),
+    // the edges around the center of the color are in place
+    Cross(Color),
+    // the first two layers, counted from the face with the center of the color
+    F2l(Color),
+    // centers turned the right way, see `Grid::are_centers_oriented`
+    CentersOriented,
+    // holds after turning the top layer some way
+    UpToAuf(Box<Goal>),
+    // holds with the cube held some way
+    UpToRotation(Box<Goal>),
+    And(Vec<Goal>),
+    Or(Vec<Goal>),
+}
+
+impl Goal {
+    pub fn and(self, other: Goal) -> Goal {
+        match self {
+            Goal::And(mut goals) => {
+                goals.push(other);
+                Goal::And(goals)
+            }
+            goal => Goal::And(vec![goal, other]),
+        }
+    }
+
+    pub fn or(self, other: Goal) -> Goal {
+        match self {
+            Goal::Or(mut goals) => {
+                goals.push(other);
+                Goal::Or(goals)
+            }
+            goal => Goal::Or(vec![goal, other]),
+        }
+    }
+
+    pub fn up_to_auf(self) -> Goal {
+        Goal::UpToAuf(Box::new(self))
+    }
+
+    pub fn up_to_rotation(self) -> Goal {
+        Goal::UpToRotation(Box::new(self))
+    }
+
+    pub fn is_met(&self, grid: &Grid) -> bool {
+        match self {
+            Goal::Solved => grid.is_solved(),
+            Goal::FaceSolved(side) => {
+                let color = center(grid, *side);
+                grid.faces[side.idx()].grid.iter().flatten().all(|&c| c == color)
+            }
+            Goal::LayerSolved(side) => are_pieces_solved(grid, |piece| touches(piece, *side)),
+            Goal::Cross(color) => match side_of(grid, *color) {
+                Some(side) => are_pieces_solved(grid, |piece| matches!(piece, Piece::Edge(_)) && touches(piece, side)),
+                None => false,
+            },
+            Goal::F2l(color) => match side_of(grid, *color) {
+                Some(side) => {
+                    let last_layer = opposite(side);
+                    are_pieces_solved(grid, |piece| !touches(piece, last_layer))
+                }
+                None => false,
+            },
+            Goal::CentersOriented => grid.are_centers_oriented(),
+            Goal::UpToAuf(goal) => {
+                let mut grid = grid.clone();
+                (0..4).any(|_| {
+                    let met = goal.is_met(&grid);
+                    grid.move_face(GridSide::Top, MoveDirection::Clockwise);
+                    met
+                })
+            }
+            Goal::UpToRotation(goal) => ROTATIONS.iter().any(|rotation| {
+                let mut grid = grid.clone();
+                grid.apply_moves(&parse_algorithm(rotation).unwrap());
+                goal.is_met(&grid)
+            }),
+            Goal::And(goals) => goals.iter().all(|goal| goal.is_met(grid)),
+            Goal::Or(goals) => goals.iter().any(|goal| goal.is_met(grid)),
+        }
+    }
+
+    // reads goals like `cross:white`, `f2l:yellow & face:U` or `auf(solved) | layer:D`;
+    // `&` binds tighter than `|`, and the sides are written as in move notation
+    pub fn parse(source: &str) -> Result<Goal, String> {
+        let mut chars = source.chars().peekable();
+        let goal = parse_or(&mut chars)?;
+
+        skip_spaces(&mut chars);
+        match chars.next() {
+            Some(c) => Err(format!("Unexpected '{}' in goal {}", c, source)),
+            None => Ok(goal),
+        }
+    }
+}
+
+type Input<'a> = Peekable<Chars<'a>>;
+
+fn skip_spaces(chars: &mut Input) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_or(chars: &mut Input) -> Result<Goal, String> {
+    let mut goal = parse_and(chars)?;
+    skip_spaces(chars);
+    while chars.next_if_eq(&'|').is_some() {
+        goal = goal.or(parse_and(chars)?);
+        skip_spaces(chars);
+    }
+    Ok(goal)
+}
+
+fn parse_and(chars: &mut Input) -> Result<Goal, String> {
+    let mut goal = parse_single(chars)?;
+    skip_spaces(chars);
+    while chars.next_if_eq(&'&').is_some() {
+        goal = goal.and(parse_single(chars)?);
+        skip_spaces(chars);
+    }
+    Ok(goal)
+}
+
+fn parse_single(chars: &mut Input) -> Result<Goal, String> {
+    skip_spaces(chars);
+    let mut word = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '-') {
+        word.push(c);
+    }
+    let word = word.to_lowercase();
+
+    if chars.next_if_eq(&'(').is_some() {
+        let inner = parse_or(chars)?;
+        skip_spaces(chars);
+        if chars.next_if_eq(&')').is_none() {
+            return Err(format!("Missing ')' after {}(", word));
+        }
+        return match word.as_str() {
+            "auf" => Ok(inner.up_to_auf()),
+            "rotated" => Ok(inner.up_to_rotation()),
+            "" => Ok(inner),
+            _ => Err(format!("Unknown goal modifier '{}'", word)),
+        };
+    }
+
+    let argument = if chars.next_if_eq(&':').is_some() {
+        let mut argument = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_alphanumeric()) {
+            argument.push(c);
+        }
+        Some(argument)
+    } else {
+        None
+    };
+
+    let side = |arg: &str| {
+        parse_side(arg).ok_or_else(|| format!("Unknown side '{}' in goal {}", arg, word))
+    };
+    let color = |arg: &str| {
+        Color::from_name(arg)
+            .or_else(|| arg.chars().next().filter(|_| arg.len() == 1).and_then(Color::from_char))
+            .ok_or_else(|| format!("Unknown color '{}' in goal {}", arg, word))
+    };
+
+    match (word.as_str(), argument.as_deref()) {
+        ("solved", None) => Ok(Goal::Solved),
+        ("centers", None) => Ok(Goal::CentersOriented),
+        ("face", Some(arg)) => Ok(Goal::FaceSolved(side(arg)?)),
+        ("layer", Some(arg)) => Ok(Goal::LayerSolved(side(arg)?)),
+        ("cross", Some(arg)) => Ok(Goal::Cross(color(arg)?)),
+        ("f2l", Some(arg)) => Ok(Goal::F2l(color(arg)?)),
+        ("", _) => Err("Expected a goal".to_string()),
+        (_, None) => Err(format!("Unknown goal '{}'", word)),
+        (_, Some(arg)) => Err(format!("Unknown goal '{}:{}'", word, arg)),
+    }
+}
+
+fn parse_side(name: &str) -> Option<GridSide> {
+    match name.to_ascii_uppercase().as_str() {
+        "U" => Some(GridSide::Top),
+        "L" => Some(GridSide::Left),
+        "F" => Some(GridSide::Front),
+        "R" => Some(GridSide::Right),
+        "B" => Some(GridSide::Back),
+        "D" => Some(GridSide::Bottom),
+        _ => None,
+    }
+}
+
+fn opposite(side: GridSide) -> GridSide {
+    match side {
+        GridSide::Top => GridSide::Bottom,
+        GridSide::Bottom => GridSide::Top,
+        GridSide::Left => GridSide::Right,
+        GridSide::Right => GridSide::Left,
+        GridSide::Front => GridSide::Back,
+        GridSide::Back => GridSide::Front,
+        _ => panic!(),
+    }
+}
+
+fn center(grid: &Grid, side: GridSide) -> Color {
+    grid.faces[side.idx()].grid[1][1]
+}
+
+fn side_of(grid: &Grid, color: Color) -> Option<GridSide> {
+    (0..6).map(GridSide::from_idx).find(|&side| center(grid, side) == color)
+}
+
+fn touches(piece: &Piece, side: GridSide) -> bool {
+    piece.stickers().iter().any(|pos| pos.side == side)
+}
+
+fn are_pieces_solved(grid: &Grid, filter: impl Fn(&Piece) -> bool) -> bool {
+    Piece::all()
+        .filter(|piece| filter(piece))
+        .all(|piece| {
+            piece.stickers().into_iter()
+                .all(|pos| grid.at(pos) == center(grid, pos.side))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::mask::MaskPreset;
+
+    #[test]
+    fn test_partial_goals() {
+        let grid = Grid::after("R U R' U'");
+        assert!(!Goal::Solved.is_met(&grid));
+        assert!(!Goal::LayerSolved(GridSide::Top).is_met(&grid));
+        assert!(Goal::LayerSolved(GridSide::Left).is_met(&Grid::after("R")));
+        assert!(Goal::Cross(Color::Yellow).is_met(&grid));
+        assert!(!Goal::F2l(Color::Yellow).is_met(&grid));
+
+        // the last layer doesn't matter for F2L
+        let grid = Grid::after("U");
+        assert!(Goal::F2l(Color::Yellow).is_met(&grid));
+        assert!(Goal::FaceSolved(GridSide::Top).is_met(&grid));
+        assert!(!Goal::FaceSolved(GridSide::Front).is_met(&grid));
+        assert!(!Goal::Cross(Color::White).is_met(&grid));
+        assert!(Goal::Solved.up_to_auf().is_met(&grid));
+
+        let grid = Grid::after("x y");
+        assert!(Goal::Solved.is_met(&grid));
+        assert!(!Goal::FaceSolved(GridSide::Top).is_met(&Grid::after("R")));
+        assert!(Goal::FaceSolved(GridSide::Top).up_to_rotation().is_met(&Grid::after("R")));
+    }
+
+    #[test]
+    fn test_gray_stickers_never_match() {
+        // like `Goal::Solved`, every goal needs the real colors
+        let grid = Grid::new().masked(MaskPreset::LastLayer);
+        assert!(!Goal::Solved.is_met(&grid));
+        assert!(!Goal::FaceSolved(GridSide::Front).is_met(&grid));
+        assert!(!Goal::Cross(Color::Yellow).is_met(&grid));
+        assert!(!Goal::F2l(Color::Yellow).is_met(&grid));
+        assert!(Goal::FaceSolved(GridSide::Top).is_met(&grid));
+    }
+
+    #[test]
+    fn test_combined_goals() {
+        let grid = Grid::after("U");
+        let cross = Goal::Cross(Color::Yellow);
+
+        assert!(cross.clone().and(Goal::FaceSolved(GridSide::Top)).is_met(&grid));
+        assert!(!cross.clone().and(Goal::Solved).is_met(&grid));
+        assert!(cross.clone().or(Goal::Solved).is_met(&grid));
+        assert!(!Goal::Solved.or(Goal::Cross(Color::White)).is_met(&grid));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Goal::parse("solved").unwrap(), Goal::Solved);
+        assert_eq!(
+            Goal::parse("cross:white & f2l:W | auf(solved)").unwrap(),
+            Goal::Or(vec![
+                Goal::And(vec![Goal::Cross(Color::White), Goal::F2l(Color::White)]),
+                Goal::Solved.up_to_auf(),
+            ])
+        );
+        assert_eq!(
+            Goal::parse("rotated(face:u & layer:D)").unwrap(),
+            Goal::FaceSolved(GridSide::Top).and(Goal::LayerSolved(GridSide::Bottom)).up_to_rotation()
+        );
+
+        assert!(Goal::parse("cross:purple").is_err());
+        assert!(Goal::parse("face:X").is_err());
+        assert!(Goal::parse("auf(solved").is_err());
+        assert!(Goal::parse("solved &").is_err());
+        assert!(Goal::parse("solved solved").is_err());
+    }
+}
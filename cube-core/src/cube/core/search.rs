@@ -0,0 +1,165 @@
+use crate::cube::{
+    slice::CubeMove,
+    core::grid::{Grid, GridSide, MoveDirection}
+};
+
+pub trait CostModel {
+    fn move_cost(&self, mv: &CubeMove) -> u32;
+
+    fn cost(&self, moves: &[CubeMove]) -> u32 {
+        moves.iter().map(|mv| self.move_cost(mv)).sum()
+    }
+}
+
+impl<F: Fn(&CubeMove) -> u32> CostModel for F {
+    fn move_cost(&self, mv: &CubeMove) -> u32 {
+        self(mv)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    // half turn metric, slice moves count as two turns
+    Htm,
+    // quarter turn metric, slice moves count as two turns
+    Qtm,
+    // slice turn metric, every move counts as one
+    Stm,
+}
+
+impl CostModel for Metric {
+    fn move_cost(&self, mv: &CubeMove) -> u32 {
+        let turns = match (self, mv.direction) {
+            (Metric::Qtm, MoveDirection::Double) => 2,
+            _ => 1,
+        };
+
+        match self {
+            Metric::Stm => turns,
+            _ if mv.grid_side.is_middle() => turns * 2,
+            _ => turns,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub moves: Vec<CubeMove>,
+    pub cost: u32,
+}
+
+// every turn of the given layers: clockwise, counter clockwise and double
+pub fn move_set(sides: &[GridSide]) -> Vec<CubeMove> {
+    sides.iter()
+        .flat_map(|&side| {
+            [MoveDirection::Clockwise, MoveDirection::CounterClockwise, MoveDirection::Double]
+                .into_iter()
+                .map(move |direction| CubeMove::from_side(side, direction))
+        })
+        .collect()
+}
+
+struct Search<'a, G> {
+    goal: G,
+    moves: &'a [CubeMove],
+    path: Vec<CubeMove>,
+    found: Vec<Vec<CubeMove>>,
+}
+
+impl<G: Fn(&Grid) -> bool> Search<'_, G> {
+    // turns of the same axis commute, so only the ones going from the first
+    // to the last layer are tried, which also skips turning one layer twice
+    fn is_redundant(&self, mv: &CubeMove) -> bool {
+        match self.path.last() {
+            Some(last) => last.axis == mv.axis && last.order.idx() >= mv.order.idx(),
+            None => false,
+        }
+    }
+
+    fn run(&mut self, grid: &mut Grid, depth_left: usize) {
+        if !self.path.is_empty() && (self.goal)(grid) {
+            self.found.push(self.path.clone());
+        }
+        if depth_left == 0 {
+            return;
+        }
+
+        for mv in self.moves {
+            if self.is_redundant(mv) {
+                continue;
+            }
+
+            grid.move_face(mv.grid_side, mv.direction);
+            self.path.push(mv.clone());
+
+            self.run(grid, depth_left - 1);
+
+            self.path.pop();
+            grid.move_face(mv.grid_side, mv.direction.flip());
+        }
+    }
+}
+
+// finds every sequence of at most `max_depth` moves from the set that takes
+// `start` to a position satisfying `goal`, cheapest first
+pub fn search<G, C>(start: &Grid, goal: G, moves: &[CubeMove], max_depth: usize, cost: &C) -> Vec<Solution>
+where
+    G: Fn(&Grid) -> bool,
+    C: CostModel,
+{
+    let mut search = Search {
+        goal,
+        moves,
+        path: Vec::with_capacity(max_depth),
+        found: Vec::new(),
+    };
+    search.run(&mut start.clone(), max_depth);
+
+    let mut solutions: Vec<Solution> = search.found.into_iter()
+        .map(|moves| Solution { cost: cost.cost(&moves), moves })
+        .collect();
+    solutions.sort_by_key(|s| (s.cost, s.moves.len()));
+
+    solutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::algorithm::{algorithm_to_string, parse_algorithm};
+    use GridSide::*;
+
+    #[test]
+    fn test_search_finds_inverse() {
+        let start = Grid::after("U R U' R'");
+        let solutions = search(&start, |g| g.is_solved(), &move_set(&[Right, Top]), 4, &Metric::Htm);
+
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(algorithm_to_string(&solutions[0].moves), "R U R' U'");
+        assert_eq!(solutions[0].cost, 4);
+    }
+
+    #[test]
+    fn test_search_sorts_by_cost() {
+        let start = Grid::after("R2 L2");
+        let moves = move_set(&[Right, Left, MiddleX]);
+
+        // M2 leaves the cube solved, just turned around
+        let solutions = search(&start, |g| g.is_solved(), &moves, 3, &Metric::Stm);
+        assert!(solutions.len() > 1);
+        assert_eq!(algorithm_to_string(&solutions[0].moves), "M2");
+        assert!(solutions.windows(2).all(|w| w[0].cost <= w[1].cost));
+
+        let no_slices = |mv: &CubeMove| if mv.grid_side.is_middle() { 3 } else { 1 };
+        let solutions = search(&start, |g| g.is_solved(), &moves, 3, &no_slices);
+        assert_eq!(algorithm_to_string(&solutions[0].moves), "L2 R2");
+    }
+
+    #[test]
+    fn test_search_respects_depth() {
+        let start = Grid::after("R U F");
+        let solutions = search(&start, |g| g.is_solved(), &move_set(&[Right, Top, Front]), 2, &Metric::Htm);
+
+        assert!(solutions.is_empty());
+    }
+}
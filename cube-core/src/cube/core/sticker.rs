@@ -0,0 +1,197 @@
+use crate::{
+    utils::{
+        cube_utils::Color,
+    },
+    cube::{
+        slice::CubeMove,
+        core::{
+            center::{compose_centers, invert_centers, SOLVED_CENTERS},
+            grid::{Grid, GridFace, GridSide}
+        }
+    }
+};
+
+// a sticker is identified by the position it has on a solved cube
+pub type StickerId = u8;
+pub type StickerGrid = Grid<StickerId>;
+
+pub const STICKER_COUNT: usize = 54;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StickerPos {
+    pub side: GridSide,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl StickerPos {
+    pub fn new(side: GridSide, row: usize, col: usize) -> StickerPos {
+        StickerPos { side, row, col }
+    }
+
+    pub fn from_id(id: StickerId) -> StickerPos {
+        let id = id as usize;
+        StickerPos {
+            side: GridSide::from_idx(id / 9),
+            row: id % 9 / 3,
+            col: id % 3,
+        }
+    }
+
+    pub fn id(&self) -> StickerId {
+        (self.side.idx() * 9 + self.row * 3 + self.col) as StickerId
+    }
+
+    pub fn all() -> impl Iterator<Item = StickerPos> {
+        (0..STICKER_COUNT).map(|id| StickerPos::from_id(id as StickerId))
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    pub fn at(&self, pos: StickerPos) -> T {
+        self.faces[pos.side.idx()].grid[pos.row][pos.col]
+    }
+
+    pub fn set(&mut self, pos: StickerPos, value: T) {
+        self.faces[pos.side.idx()].grid[pos.row][pos.col] = value;
+    }
+}
+
+impl Grid<StickerId> {
+    // every sticker sitting at its own position
+    pub fn identity() -> StickerGrid {
+        let mut faces = [GridFace { grid: [[0; 3]; 3] }; 6];
+        for pos in StickerPos::all() {
+            faces[pos.side.idx()].grid[pos.row][pos.col] = pos.id();
+        }
+
+        Grid { faces, centers: SOLVED_CENTERS }
+    }
+
+    // the permutation done by the moves
+    pub fn from_moves(moves: &[CubeMove]) -> StickerGrid {
+        let mut grid = StickerGrid::identity();
+        grid.apply_moves(moves);
+        grid
+    }
+
+    // the state reached by doing `self` and then `other`
+    pub fn compose(&self, other: &StickerGrid) -> StickerGrid {
+        let mut composed = StickerGrid::identity();
+        for pos in StickerPos::all() {
+            composed.set(pos, self.at(StickerPos::from_id(other.at(pos))));
+        }
+        composed.centers = compose_centers(&self.centers, &other.centers);
+        composed
+    }
+
+    // the state undoing `self`
+    pub fn inverse(&self) -> StickerGrid {
+        let mut inverse = StickerGrid::identity();
+        for pos in StickerPos::all() {
+            inverse.set(StickerPos::from_id(self.at(pos)), pos.id());
+        }
+        inverse.centers = invert_centers(&self.centers);
+        inverse
+    }
+
+    pub fn is_identity(&self) -> bool {
+        StickerPos::all().all(|pos| self.at(pos) == pos.id())
+    }
+
+    // which sticker is at the given position
+    pub fn sticker_at(&self, pos: StickerPos) -> StickerId {
+        self.at(pos)
+    }
+
+    // where the given sticker went
+    pub fn position_of(&self, id: StickerId) -> StickerPos {
+        StickerPos::all()
+            .find(|&pos| self.at(pos) == id)
+            .expect("every sticker is somewhere on the grid")
+    }
+
+    // colors every sticker the way it was colored on `colors` before moving
+    pub fn paint(&self, colors: &Grid) -> Grid {
+        let mut painted = colors.clone();
+        for pos in StickerPos::all() {
+            painted.set(pos, colors.at(StickerPos::from_id(self.at(pos))));
+        }
+        painted.centers = compose_centers(&colors.centers, &self.centers);
+        painted
+    }
+
+    // like `paint`, but everything except the tracked stickers is gray
+    pub fn highlight(&self, colors: &Grid, tracked: &[StickerId]) -> Grid {
+        let mut painted = self.paint(colors);
+        for pos in StickerPos::all() {
+            if !tracked.contains(&self.at(pos)) {
+                painted.set(pos, Color::Gray);
+            }
+        }
+        painted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::algorithm::parse_algorithm;
+
+    #[test]
+    fn test_sticker_ids() {
+        for id in 0..STICKER_COUNT as StickerId {
+            assert_eq!(StickerPos::from_id(id).id(), id);
+        }
+
+        let grid = StickerGrid::identity();
+        assert!(grid.is_identity());
+        assert_eq!(grid.sticker_at(StickerPos::new(GridSide::Front, 0, 2)), 20);
+    }
+
+    #[test]
+    fn test_tracking_stickers() {
+        let mut grid = StickerGrid::identity();
+        let ufr = StickerPos::new(GridSide::Top, 2, 2).id();
+
+        grid.apply_moves(&parse_algorithm("R").unwrap());
+        assert_eq!(grid.position_of(ufr), StickerPos::new(GridSide::Back, 0, 0));
+        assert_eq!(grid.sticker_at(StickerPos::new(GridSide::Top, 2, 2)), StickerPos::new(GridSide::Front, 2, 2).id());
+
+        grid.apply_moves(&parse_algorithm("R'").unwrap());
+        assert!(grid.is_identity());
+    }
+
+    #[test]
+    fn test_compose_and_inverse() {
+        let first = parse_algorithm("R U R' U'").unwrap();
+        let second = parse_algorithm("F2 M' D S").unwrap();
+        let both: Vec<CubeMove> = first.iter().chain(second.iter()).cloned().collect();
+
+        let composed = StickerGrid::from_moves(&first).compose(&StickerGrid::from_moves(&second));
+        assert_eq!(composed.faces, StickerGrid::from_moves(&both).faces);
+
+        let inverse = composed.inverse();
+        assert!(composed.compose(&inverse).is_identity());
+        assert!(inverse.compose(&composed).is_identity());
+    }
+
+    #[test]
+    fn test_painting_matches_colors() {
+        let moves = parse_algorithm("R U R' U' F2 M E' S").unwrap();
+
+        let mut colors = Grid::new();
+        colors.apply_moves(&moves);
+
+        let mut stickers = StickerGrid::identity();
+        stickers.apply_moves(&moves);
+
+        assert_eq!(stickers.paint(&Grid::new()).faces, colors.faces);
+
+        let highlighted = stickers.highlight(&Grid::new(), &[0]);
+        let visible = StickerPos::all()
+            .filter(|&pos| highlighted.at(pos) != Color::Gray)
+            .collect::<Vec<StickerPos>>();
+        assert_eq!(visible, vec![stickers.position_of(0)]);
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::OnceLock;
+
+use crate::{
+    utils::{
+        cube_utils::Color,
+    },
+    cube::{
+        core::{
+            algorithm::parse_algorithm,
+            grid::{Grid, GridSide},
+            sticker::{StickerGrid, StickerPos}
+        }
+    }
+};
+
+// every orientation of the cube: one of six faces on top, turned four ways
+pub(crate) const ROTATIONS: [&str; 24] = [
+    "", "y", "y2", "y'",
+    "x", "x y", "x y2", "x y'",
+    "x2", "x2 y", "x2 y2", "x2 y'",
+    "x'", "x' y", "x' y2", "x' y'",
+    "z", "z y", "z y2", "z y'",
+    "z'", "z' y", "z' y2", "z' y'",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symmetry {
+    rotation: usize,
+    mirror: bool,
+}
+
+impl Symmetry {
+    pub fn identity() -> Symmetry {
+        Symmetry { rotation: 0, mirror: false }
+    }
+
+    // the 24 rotations, followed by the same rotations of the mirrored cube
+    pub fn all() -> impl Iterator<Item = Symmetry> {
+        [false, true].into_iter()
+            .flat_map(|mirror| (0..ROTATIONS.len()).map(move |rotation| Symmetry { rotation, mirror }))
+    }
+
+    pub fn is_mirror(&self) -> bool {
+        self.mirror
+    }
+
+    // the rotation in standard notation, done after mirroring left and right
+    pub fn rotation(&self) -> &'static str {
+        ROTATIONS[self.rotation]
+    }
+
+    pub fn inverse(&self) -> Symmetry {
+        let permutation = self.permutation();
+        Symmetry::all()
+            .find(|sym| permutation.compose(&sym.permutation()).is_identity())
+            .expect("every symmetry has an inverse")
+    }
+
+    // where every sticker is taken by the symmetry
+    fn permutation(&self) -> StickerGrid {
+        static PERMUTATIONS: OnceLock<Vec<StickerGrid>> = OnceLock::new();
+
+        let permutations = PERMUTATIONS.get_or_init(|| {
+            Symmetry::all()
+                .map(|sym| {
+                    let rotation = StickerGrid::from_moves(&parse_algorithm(ROTATIONS[sym.rotation]).unwrap());
+                    if sym.mirror {
+                        mirror_permutation().compose(&rotation)
+                    } else {
+                        rotation
+                    }
+                })
+                .collect()
+        });
+
+        permutations[self.mirror as usize * ROTATIONS.len() + self.rotation].clone()
+    }
+}
+
+// reflects the cube through the plane between the left and right faces
+fn mirror_permutation() -> StickerGrid {
+    let mut mirror = StickerGrid::identity();
+    for pos in StickerPos::all() {
+        let side = match pos.side {
+            GridSide::Left => GridSide::Right,
+            GridSide::Right => GridSide::Left,
+            side => side,
+        };
+        mirror.set(pos, StickerPos::new(side, pos.row, 2 - pos.col).id());
+    }
+    mirror
+}
+
+fn center_colors(grid: &Grid) -> [Color; 6] {
+    std::array::from_fn(|idx| grid.faces[idx].grid[1][1])
+}
+
+// moves the stickers with the symmetry and then renames the colors, so that
+// every center shows the color given for its face in `centers`; a color is
+// renamed after the face its center was moved to, gray stays gray and of two
+// centers with the same color only the first one counts
+fn transform(grid: &Grid, sym: &Symmetry, centers: &[Color; 6]) -> Grid {
+    let permutation = sym.permutation();
+    let mut transformed = permutation.paint(grid);
+
+    let mut renamed: Vec<(Color, Color)> = Vec::with_capacity(6);
+    for (idx, &center) in centers.iter().enumerate() {
+        let from = StickerPos::from_id(permutation.at(StickerPos::new(GridSide::from_idx(idx), 1, 1)));
+        let color = grid.at(from);
+        if color != Color::Gray && !renamed.iter().any(|&(c, _)| c == color) {
+            renamed.push((color, center));
+        }
+    }
+
+    for pos in StickerPos::all() {
+        let color = transformed.at(pos);
+        if let Some(&(_, to)) = renamed.iter().find(|&&(c, _)| c == color) {
+            transformed.set(pos, to);
+        }
+    }
+    transformed
+}
+
+impl Grid {
+    // the same position seen through the symmetry, keeping the colors
+    // of the centers where they are
+    pub fn apply_symmetry(&self, sym: &Symmetry) -> Grid {
+        transform(self, sym, &center_colors(self))
+    }
+
+    // one representative of all positions equal to this one up to symmetry,
+    // with the symmetry that turns this position into it, so that
+    // `apply_symmetry` with it gives the representative back
+    pub fn canonical(&self) -> (Grid, Symmetry) {
+        Symmetry::all()
+            .map(|sym| (self.apply_symmetry(&sym), sym))
+            .min_by_key(|(grid, _)| StickerPos::all().map(|pos| grid.at(pos)).collect::<Vec<Color>>())
+            .expect("there is always a symmetry")
+    }
+
+    // the other position is first renamed to the colors of these centers,
+    // so the same position recorded from a different angle or with
+    // a different scheme is still symmetric
+    pub fn is_symmetric_to(&self, other: &Grid) -> bool {
+        let other = transform(other, &Symmetry::identity(), &center_colors(self));
+        self.canonical().0.faces == other.canonical().0.faces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::color_scheme::ColorScheme;
+
+    #[test]
+    fn test_symmetries_are_distinct() {
+        let permutations: Vec<StickerGrid> = Symmetry::all().map(|sym| sym.permutation()).collect();
+        assert_eq!(permutations.len(), 48);
+
+        for (i, a) in permutations.iter().enumerate() {
+            for b in permutations.iter().skip(i + 1) {
+                assert_ne!(a.faces, b.faces);
+            }
+        }
+
+        for sym in Symmetry::all() {
+            assert!(sym.permutation().compose(&sym.inverse().permutation()).is_identity());
+        }
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mirror = Symmetry::all().find(|sym| sym.is_mirror() && sym.rotation().is_empty()).unwrap();
+
+        let grid = Grid::after("R U R' U'");
+        assert_eq!(grid.apply_symmetry(&mirror).faces, Grid::after("L' U' L U").faces);
+    }
+
+    #[test]
+    fn test_canonical_forms() {
+        let (solved, _) = Grid::new().canonical();
+        assert!(solved.is_solved());
+
+        let grid = Grid::after("R U R' U' F2");
+        let (canonical, sym) = grid.canonical();
+        assert_eq!(grid.apply_symmetry(&sym).faces, canonical.faces);
+
+        assert!(grid.is_symmetric_to(&Grid::after("y R U R' U' F2 y'")));
+        assert!(grid.is_symmetric_to(&Grid::after("L' U' L U F2")));
+        assert!(grid.is_symmetric_to(&Grid::after("x R U R' U' F2")));
+        assert!(!grid.is_symmetric_to(&Grid::after("R U R' U F2")));
+    }
+
+    #[test]
+    fn test_other_schemes() {
+        let mut grid = Grid::with_scheme(&ColorScheme::japanese());
+        grid.apply_moves(&parse_algorithm("R U R' U' F2").unwrap());
+
+        let (canonical, sym) = grid.canonical();
+        assert_eq!(grid.apply_symmetry(&sym).faces, canonical.faces);
+        assert_eq!(center_colors(&canonical), center_colors(&grid));
+        assert!(grid.is_symmetric_to(&Grid::after("y R U R' U' F2 y'")));
+        assert!(!grid.is_symmetric_to(&Grid::after("R U R' U F2")));
+    }
+}
@@ -1,27 +1,28 @@
 use core::f32;
-use std::collections::HashMap;
 
 use crate::{
     utils::{
-        geometry::Point3D
+        geometry::{Matrix3, Point3D, Transform}
     },
     cube::{
         slice::CubeMove,
         core::{
             grid::{Grid, GridFace, GridSide},
+            mask::MaskPreset,
+            orientation::Orientation,
         }
     },
     game::render::{AnyFace, Renderable}
 };
 
-const CUBE_SIZE: f32 = 2.0;
-const TIEBRAKER_ROTATION: f32 = 0.00390625;
+pub const CUBE_SIZE: f32 = 2.0;
 
 #[derive(Clone)]
 pub struct Face {
     pub corners: [Point3D; 4],
     pub markers: Vec<Point3D>,
     pub grid_face: GridFace,
+    pub center_mark: Option<u8>,
 }
 
 impl Face {
@@ -34,7 +35,7 @@ impl Face {
                 corners[1].add(&diff.scalar_multiply(i as f32)));
         }
 
-        Face { corners, markers, grid_face }
+        Face { corners, markers, grid_face, center_mark: None }
     }
 
     fn create_markers(markers: &mut Vec<Point3D>, v1: Point3D, v2: Point3D) {
@@ -58,137 +59,186 @@ impl Face {
         }
     }
 
-    pub fn avg_z(&self) -> f32 {
-        (self.corners[0].z + self.corners[1].z + self.corners[2].z + self.corners[3].z) / 4.0
+    // point on the face, in sticker rows and columns from the first corner
+    fn point_at(&self, row: f32, col: f32) -> Point3D {
+        let row_step = self.markers[4].subtract(&self.markers[0]);
+        let col_step = self.markers[1].subtract(&self.markers[0]);
+        self.markers[0]
+            .add(&row_step.scalar_multiply(row))
+            .add(&col_step.scalar_multiply(col))
+    }
+
+    // arrow on the center sticker pointing where its top is turned
+    pub fn center_mark_triangle(&self) -> Option<[Point3D; 3]> {
+        let turns = self.center_mark?;
+
+        // pointing up, as (row, col) offsets from the middle of the center
+        let mut points = [(-0.35, 0.0), (0.15, -0.25), (0.15, 0.25)];
+        for _ in 0..turns {
+            points = points.map(|(row, col)| (col, -row));
+        }
+
+        Some(points.map(|(row, col)| self.point_at(1.5 + row, 1.5 + col)))
     }
 }
 
 pub struct Cube {
     pub position: Point3D,
+    // faces of the cube held straight, placed in the view by `transform`
     pub faces: Vec<Face>,
-    side_map: HashMap<GridSide, GridSide>,
+    center_marks: bool,
+    // hides part of the stickers when drawn, the grid itself keeps its colors
+    mask: Option<MaskPreset>,
+    // the starting view, looking at the cube from above and the side
+    view: Matrix3,
+    // how the cube is held, deciding which faces the moves turn
+    orientation: Orientation,
+    // free rotation on top of the held orientation, done in the starting view
+    offset: Matrix3,
 }
 
 impl Cube {
     pub fn new(position: (f32, f32, f32), rotation_y: f32, rotation_x: f32) -> Cube {
         let (x, y, z) = position;
-        let position = Point3D {x, y, z};
-        let corners: Vec<Point3D> = Cube::initial_corners().into_iter()
-            .map(|p| p.translate(position))
-            .collect();
-        let faces = vec![
+        Cube {
+            position: Point3D { x, y, z },
+            faces: Cube::model_faces(),
+            center_marks: false,
+            mask: None,
+            view: Matrix3::rotation_x(rotation_x).multiply(&Matrix3::rotation_y(rotation_y)),
+            orientation: Orientation::identity(),
+            offset: Matrix3::identity(),
+        }
+    }
+
+    // faces of a cube held straight around the origin
+    fn model_faces() -> Vec<Face> {
+        let corners = Cube::initial_corners();
+        vec![
             Face::new([corners[2], corners[3], corners[0], corners[1]], GridFace::empty()),
             Face::new([corners[2], corners[1], corners[5], corners[6]], GridFace::empty()),
             Face::new([corners[1], corners[0], corners[4], corners[5]], GridFace::empty()),
             Face::new([corners[0], corners[3], corners[7], corners[4]], GridFace::empty()),
             Face::new([corners[3], corners[2], corners[6], corners[7]], GridFace::empty()),
             Face::new([corners[5], corners[4], corners[7], corners[6]], GridFace::empty()),
-        ];
-
-        let mut cube = Cube {
-            position,
-            faces,
-            side_map: HashMap::new(),
-        };
-        cube.rotate_y(rotation_y);
-        cube.rotate_x(rotation_x);
-        cube.update_side_map();
-        cube
-    }
-
-    pub fn update_side_map(&mut self) {
-        let mut side_map = HashMap::new();
-
-        self.rotate_x(-TIEBRAKER_ROTATION);
-        self.rotate_y(TIEBRAKER_ROTATION);
-        self.rotate_z(TIEBRAKER_ROTATION);
-
-        for &side in &[GridSide::Right, GridSide::Left, GridSide::Top, GridSide::Bottom, GridSide::Front, GridSide::Back] {
-            let (best_face_idx, _) = self.faces
-                .iter()
-                .enumerate()
-                .map(|(i, face)| {
-                    let center = face.center();
-                    let value = match side {
-                        GridSide::Right => center.x,
-                        GridSide::Left => -center.x,
-                        GridSide::Top => center.y,
-                        GridSide::Bottom => -center.y,
-                        GridSide::Front => -center.z,
-                        GridSide::Back => center.z,
-                        _ => panic!()
-                    };
-                    (i, value)
-                })
-                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-                .unwrap();
-
-            let actual_side = GridSide::from_idx(best_face_idx);
-            side_map.insert(side, actual_side);
-        }
+        ]
+    }
 
-        self.rotate_z(-TIEBRAKER_ROTATION);
-        self.rotate_y(-TIEBRAKER_ROTATION);
-        self.rotate_x(TIEBRAKER_ROTATION);
+    // turns the faces from the model to the view; the model itself never
+    // changes, so turning the cube many times doesn't pile up rounding errors
+    pub fn rotation(&self) -> Matrix3 {
+        self.view
+            .multiply(&self.offset)
+            .multiply(&orientation_matrix(self.orientation))
+    }
 
-        self.side_map = side_map;
+    // turns the cube around its middle by the rotation, given as seen on screen
+    fn turn_in_view(&mut self, rotation: Matrix3) {
+        self.offset = self.view.transpose()
+            .multiply(&rotation)
+            .multiply(&self.view)
+            .multiply(&self.offset)
+            .orthonormalize();
+        self.hold_nearest();
     }
 
-    pub fn rotate_x(&mut self, angle: f32) {
-        let flipped_offset = self.position.scalar_multiply(-1.0);
-        for face in self.faces.iter_mut() {
-            for p in &mut face.corners {
-                *p = p.translate(flipped_offset);
-                *p = p.rotate_x(angle);
-                *p = p.translate(self.position);
-            }
-            for p in &mut face.markers {
-                *p = p.translate(flipped_offset);
-                *p = p.rotate_x(angle);
-                *p = p.translate(self.position);
-            }
+    // moves as much of the offset into the orientation as possible, so
+    // the moves go to the faces nearest to where they are typed
+    fn hold_nearest(&mut self) {
+        let held = self.offset.multiply(&orientation_matrix(self.orientation));
+        let columns = held.transpose();
+        let x = nearest_axis(columns.rows[0], None);
+        let y = nearest_axis(columns.rows[1], Some(x));
+        let nearest = Matrix3 { rows: [x, y, x.cross(&y)] }.transpose();
+
+        // faces of the straight cube the nearest rotation brings to the front and top
+        let front = nearest.transpose().apply(side_direction(GridSide::Front));
+        let top = nearest.transpose().apply(side_direction(GridSide::Top));
+        let orientation = Orientation::from_sides(side_at(front), side_at(top))
+            .expect("the nearest rotation holds the cube some way");
+
+        if orientation != self.orientation {
+            self.orientation = orientation;
+            self.offset = held.multiply(&orientation_matrix(orientation).transpose());
         }
     }
 
+    pub fn rotate_x(&mut self, angle: f32) {
+        self.turn_in_view(Matrix3::rotation_x(angle));
+    }
+
     pub fn rotate_y(&mut self, angle: f32) {
-        let flipped_offset = self.position.scalar_multiply(-1.0);
-        for face in self.faces.iter_mut() {
-            for p in &mut face.corners {
-                *p = p.translate(flipped_offset);
-                *p = p.rotate_y(angle);
-                *p = p.translate(self.position);
-            }
-            for p in &mut face.markers {
-                *p = p.translate(flipped_offset);
-                *p = p.rotate_y(angle);
-                *p = p.translate(self.position);
-            }
-        }
+        self.turn_in_view(Matrix3::rotation_y(angle));
     }
 
     pub fn rotate_z(&mut self, angle: f32) {
-        let flipped_offset = self.position.scalar_multiply(-1.0);
-        for face in self.faces.iter_mut() {
-            for p in &mut face.corners {
-                *p = p.translate(flipped_offset);
-                *p = p.rotate_z(angle);
-                *p = p.translate(self.position);
-            }
-            for p in &mut face.markers {
-                *p = p.translate(flipped_offset);
-                *p = p.rotate_z(angle);
-                *p = p.translate(self.position);
-            }
-        }
+        self.turn_in_view(Matrix3::rotation_z(angle));
+    }
+
+    pub fn rotate_around(&mut self, axis: Point3D, angle: f32) {
+        self.turn_in_view(Matrix3::rotation_around(axis, angle));
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    // how far the cube is turned away from being held straight in the orientation
+    pub fn offset(&self) -> Matrix3 {
+        self.offset
+    }
+
+    // holds the cube exactly in the orientation, with no free rotation
+    pub fn hold(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+        self.offset = Matrix3::identity();
+    }
+
+    // turns the cube straight in the nearest orientation
+    pub fn snap(&mut self) {
+        self.hold(self.orientation);
+    }
+
+    // which side of the grid is seen on the given side of the view
+    pub fn facing(&self, side: GridSide) -> GridSide {
+        self.orientation.facing(side)
+    }
+
+    // direction from the middle of the cube out through the face of the grid side
+    pub fn face_normal(&self, side: GridSide) -> Point3D {
+        self.rotation().apply(side_direction(side))
     }
 
     pub fn apply_grid(&mut self, grid: &Grid) {
+        let grid = &self.shown(grid);
         self.faces[0].grid_face = grid.faces[0].clone();
         self.faces[1].grid_face = grid.faces[1].clone();
         self.faces[2].grid_face = grid.faces[2].clone();
         self.faces[3].grid_face = grid.faces[3].clone();
         self.faces[4].grid_face = grid.faces[4].clone();
         self.faces[5].grid_face = grid.faces[5].clone();
+
+        for (face, center) in self.faces.iter_mut().zip(grid.centers.iter()) {
+            face.center_mark = self.center_marks.then_some(center.turns);
+        }
+    }
+
+    // draw how the centers are turned, for solving like a supercube
+    pub fn show_center_marks(&mut self, show: bool) {
+        self.center_marks = show;
+    }
+
+    // gray out what the preset hides, following the pieces as they move
+    pub fn set_mask(&mut self, mask: Option<MaskPreset>) {
+        self.mask = mask;
+    }
+
+    // the grid as it is drawn
+    pub fn shown(&self, grid: &Grid) -> Grid {
+        match self.mask {
+            Some(mask) => grid.masked(mask),
+            None => grid.clone(),
+        }
     }
 
     fn initial_corners() -> [Point3D; 8] {
@@ -207,38 +257,132 @@ impl Cube {
     }
 
     pub fn translate_move(&self, cube_move: CubeMove) -> CubeMove {
-        let side = cube_move.grid_side;
-        let mut direction = cube_move.direction;
-        let translated = self.side_map.get(&side.middle_layer_adjacent()).unwrap().clone();
-        if side.is_middle() {
-            let translated_middle = GridSide::middle_layer_from_axis(&translated.axis());
-            if translated.idx() != translated_middle.middle_layer_adjacent().idx() {
-                direction = direction.flip();
-            }
-            CubeMove::from_side(translated_middle, direction)
-        } else {
-            CubeMove::from_side(translated, direction)
-        }
+        self.orientation.translate_move(cube_move)
     }
 }
 
-impl Renderable for Cube {
-    fn get_visible_faces(&self) -> Vec<AnyFace> {
-        let mut faces_clone: Vec<AnyFace> = self.faces.clone()
-            .into_iter()
-            .map(|f| AnyFace::Face(f))
-            .collect();
+const SIDES: [GridSide; 6] = [
+    GridSide::Top, GridSide::Left, GridSide::Front, GridSide::Right, GridSide::Back, GridSide::Bottom,
+];
+
+// direction the face on the side points to, with the cube held straight
+fn side_direction(side: GridSide) -> Point3D {
+    let (x, y, z) = match side {
+        GridSide::Right => (1.0, 0.0, 0.0),
+        GridSide::Left => (-1.0, 0.0, 0.0),
+        GridSide::Top => (0.0, 1.0, 0.0),
+        GridSide::Bottom => (0.0, -1.0, 0.0),
+        GridSide::Front => (0.0, 0.0, -1.0),
+        GridSide::Back => (0.0, 0.0, 1.0),
+        _ => panic!(),
+    };
+    Point3D { x, y, z }
+}
+
+fn side_at(direction: Point3D) -> GridSide {
+    *SIDES.iter()
+        .find(|&&side| side_direction(side).dot(&direction) > 0.5)
+        .unwrap()
+}
 
-        faces_clone.sort_by(|a, b| a.avg_z().partial_cmp(&b.avg_z()).unwrap());
-        faces_clone
+// turns a cube held straight so that each face is where the orientation holds it
+fn orientation_matrix(orientation: Orientation) -> Matrix3 {
+    Matrix3 {
+        rows: [GridSide::Right, GridSide::Top, GridSide::Back]
+            .map(|side| side_direction(orientation.facing(side))),
     }
+}
+
+// the axis closest to the direction, leaving out the one along `other`
+fn nearest_axis(direction: Point3D, other: Option<Point3D>) -> Point3D {
+    let axes = [
+        Point3D { x: 1.0, y: 0.0, z: 0.0 },
+        Point3D { x: 0.0, y: 1.0, z: 0.0 },
+        Point3D { x: 0.0, y: 0.0, z: 1.0 },
+    ];
+
+    axes.into_iter()
+        .filter(|axis| other.is_none_or(|other| axis.dot(&other).abs() < 0.5))
+        .flat_map(|axis| [axis, axis.scalar_multiply(-1.0)])
+        .max_by(|a, b| a.dot(&direction).partial_cmp(&b.dot(&direction)).unwrap())
+        .unwrap()
+}
 
-    fn dist(&self) -> f32 {
-        let mut sum = 0.0;
+impl Renderable for Cube {
+    fn visit_faces(&self, visit: &mut dyn FnMut(AnyFace)) {
         for face in &self.faces {
-            sum += face.avg_z();
+            visit(AnyFace::Face(face));
+        }
+    }
+
+    fn transform(&self) -> Transform {
+        Transform { rotation: self.rotation(), position: self.position }
+    }
+
+    fn center(&self) -> Point3D {
+        Point3D { x: 0.0, y: 0.0, z: 0.0 }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::grid::MoveDirection;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn cube() -> Cube {
+        Cube::new((0.0, 0.0, 5.0), FRAC_PI_4, -FRAC_PI_4)
+    }
+
+    #[test]
+    fn test_orientation_follows_turns() {
+        let mut cube = cube();
+        assert_eq!(cube.orientation(), Orientation::identity());
+
+        // turned like x, the bottom comes to the front
+        cube.rotate_around(cube.face_normal(GridSide::Right), -FRAC_PI_2);
+        assert_eq!(cube.facing(GridSide::Front), GridSide::Bottom);
+        assert_eq!(cube.facing(GridSide::Top), GridSide::Front);
+        assert_eq!(cube.facing(GridSide::Right), GridSide::Right);
+        assert_eq!(cube.orientation(), Orientation::identity().turn(GridSide::Right, MoveDirection::Clockwise));
+
+        // past halfway to the next orientation, moves go to its faces
+        let held = cube.orientation();
+        let up = cube.face_normal(cube.facing(GridSide::Top));
+        cube.rotate_around(up, FRAC_PI_4 - 0.01);
+        assert_eq!(cube.orientation(), held);
+        cube.rotate_around(up, 0.02);
+        assert_eq!(cube.orientation(), held.turn(GridSide::Top, MoveDirection::CounterClockwise));
+        cube.rotate_around(up, -0.02);
+        assert_eq!(cube.orientation(), held);
+
+        // a slightly crooked cube snaps back to where it was held
+        cube.rotate_around(up, -FRAC_PI_4 + 0.01);
+        cube.snap();
+        let straight = cube.rotation();
+        cube.rotate_x(0.3);
+        cube.rotate_y(-0.2);
+        cube.snap();
+        for (p, q) in cube.rotation().rows.iter().zip(straight.rows.iter()) {
+            assert!(p.subtract(q).dot(&p.subtract(q)) < 1e-6);
+        }
+        assert_eq!(cube.offset(), Matrix3::identity());
+    }
+
+    #[test]
+    fn test_no_drift_after_long_rotation() {
+        let mut cube = cube();
+        for i in 0..20000 {
+            cube.rotate_x(0.013);
+            cube.rotate_y(if i % 3 == 0 { -0.007 } else { 0.011 });
         }
 
-        sum / 6.0
+        // the cube keeps its shape: turning it is still a rotation
+        let rotation = cube.rotation();
+        for (i, a) in rotation.rows.iter().enumerate() {
+            assert!((a.dot(a) - 1.0).abs() < 1e-4);
+            for b in rotation.rows.iter().skip(i + 1) {
+                assert!(a.dot(b).abs() < 1e-4);
+            }
+        }
     }
-}
\ No newline at end of file
+}
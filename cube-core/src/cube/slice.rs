@@ -1,7 +1,7 @@
 use crate::{
     utils::{
         cube_utils::{Axis, Color},
-        geometry::Point3D
+        geometry::{Matrix3, Point3D, Transform}
     },
     cube::{
         cube::Face,
@@ -12,14 +12,12 @@ use crate::{
     game::render::{AnyFace, Renderable}
 };
 
-const TIEBREAKER_WEIGHT: f32 = 0.001;
-const MAGIC_THINGY: f32 = 0.2;
-
 #[derive(Clone)]
 pub struct FaceSlice {
-    corners: [Point3D; 4],
     pub markers: Vec<Point3D>,
     pub colors: [Color; 3],
+    // the mark of the center sticker, for the slices holding a center
+    pub center_mark: Option<[Point3D; 3]>,
 }
 
 impl FaceSlice {
@@ -31,23 +29,18 @@ impl FaceSlice {
             markers.push(corners[1].add(&diff.scalar_multiply(i as f32)));
         };
 
-        FaceSlice { corners, markers, colors }
-    }
-
-    fn avg_x(&self) -> f32 {
-        (self.corners[0].x.abs() + self.corners[1].x.abs() + self.corners[2].x.abs() + self.corners[3].x.abs()) / 4.0
-    }
-
-    fn avg_y(&self) -> f32 {
-        (self.corners[0].y.abs() + self.corners[1].y.abs() + self.corners[2].y.abs() + self.corners[3].y.abs()) / 4.0
+        FaceSlice { markers, colors, center_mark: None }
     }
 
-    pub fn avg_z(&self) -> f32 {
-        (self.corners[0].z + self.corners[1].z + self.corners[2].z + self.corners[3].z) / 4.0 + MAGIC_THINGY
+    // middle of the sticker in the middle
+    fn middle(&self) -> Point3D {
+        self.markers[2..6].iter()
+            .fold(Point3D { x: 0.0, y: 0.0, z: 0.0 }, |sum, p| sum.add(p))
+            .scalar_multiply(0.25)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CubeMove {
     pub axis: Axis,
     pub grid_side: GridSide,
@@ -84,9 +77,34 @@ impl CubeMove {
 
         Ok((grid_side, direction))
     }
+
+    pub fn inverse(&self) -> CubeMove {
+        CubeMove::from_side(self.grid_side, self.direction.flip())
+    }
+
+    pub fn to_notation(&self) -> String {
+        let side = match self.grid_side {
+            GridSide::Right => "R",
+            GridSide::Left => "L",
+            GridSide::Top => "U",
+            GridSide::Bottom => "D",
+            GridSide::Front => "F",
+            GridSide::Back => "B",
+            GridSide::MiddleX => "M",
+            GridSide::MiddleY => "E",
+            GridSide::MiddleZ => "S",
+        };
+        let suffix = match self.direction {
+            MoveDirection::Clockwise => "",
+            MoveDirection::CounterClockwise => "'",
+            MoveDirection::Double => "2",
+        };
+
+        format!("{}{}", side, suffix)
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CubeSliceOrder {
     FIRST,
     MIDDLE,
@@ -103,17 +121,22 @@ impl CubeSliceOrder {
     }
 }
 
+// a layer of the cube in model space, turning on its own during a move
 #[derive(Clone)]
 pub struct CubeSlice {
-    pub global_cube_position: Point3D,
+    // where the whole cube is
+    pub cube_transform: Transform,
     pub face_1: Face,
     pub face_2: Face,
     pub face_slices: [FaceSlice; 4],
+    order: CubeSliceOrder,
+    axis: Point3D,
+    angle: f32,
 }
 
 impl CubeSlice {
     pub fn new(
-        global_cube_position: Point3D,
+        cube_transform: Transform,
         face_1: Face, 
         face_2: Face, 
         mut colors: Vec<[Color; 3]>, 
@@ -121,7 +144,7 @@ impl CubeSlice {
         order: CubeSliceOrder
     ) -> CubeSlice {
 
-        Self::flip_colors(&mut colors, axis, order);
+        Self::flip_colors(&mut colors, axis, &order);
 
         let face_slices = [
             FaceSlice::new(
@@ -162,17 +185,18 @@ impl CubeSlice {
             ),
         ];
 
-        CubeSlice { global_cube_position, face_1, face_2, face_slices }
+        let axis = face_2.center().subtract(&face_1.center());
+        CubeSlice { cube_transform, face_1, face_2, face_slices, order, axis, angle: 0.0 }
     }
 
     fn flip_colors(
         colors: &mut Vec<[Color; 3]>, 
         axis: &Axis, 
-        order: CubeSliceOrder
+        order: &CubeSliceOrder
     ) {
         match axis {
             Axis::X => {
-                if let CubeSliceOrder::LAST = &order { } else {
+                if let CubeSliceOrder::LAST = order { } else {
                     for i in 0..4 {
                         if let Some(color) = colors.get_mut(i) {
                             color.reverse();
@@ -181,7 +205,7 @@ impl CubeSlice {
                 }
             },
             Axis::Y => {
-                if let CubeSliceOrder::LAST = &order {
+                if let CubeSliceOrder::LAST = order {
                     colors.rotate_right(2);
                 } else {
                     for i in 0..4 {
@@ -192,7 +216,7 @@ impl CubeSlice {
                 }
             },
             Axis::Z => {
-                if let CubeSliceOrder::LAST = &order { } else {
+                if let CubeSliceOrder::LAST = order { } else {
                     for i in 0..4 {
                         if let Some(color) = colors.get_mut(i) {
                             color.reverse();
@@ -203,107 +227,60 @@ impl CubeSlice {
         }
     }
 
-    pub fn rotate_around_own_axis(&mut self, angle_rad: f32) {
-        let center1 = self.face_1.center();
-        let center2 = self.face_2.center();
-        let axis = center2.subtract(&center1);
-
-        for p in &mut self.face_1.corners {
-            *p = p.rotate_around_axis(axis, center1, angle_rad);
-        }
-        for p in &mut self.face_1.markers {
-            *p = p.rotate_around_axis(axis, center1, angle_rad);
-        }
-
-        for p in &mut self.face_2.corners {
-            *p = p.rotate_around_axis(axis, center1, angle_rad);
-        }
-        for p in &mut self.face_2.markers {
-            *p = p.rotate_around_axis(axis, center1, angle_rad);
+    // the centers turn with the middle layer, so the marks on them are
+    // moved over from the faces of the cube to the face slices holding them
+    pub fn carry_center_marks(&mut self, faces: &[Face]) {
+        for face_slice in &mut self.face_slices {
+            let middle = face_slice.middle();
+            face_slice.center_mark = faces.iter()
+                .find(|face| {
+                    let offset = face.center().subtract(&middle);
+                    offset.dot(&offset) < 1e-4
+                })
+                .and_then(Face::center_mark_triangle);
         }
+    }
 
-        for slice in &mut self.face_slices {
-            for p in &mut slice.corners {
-                *p = p.rotate_around_axis(axis, center1, angle_rad);
-            }
-            for p in &mut slice.markers {
-                *p = p.rotate_around_axis(axis, center1, angle_rad);
-            }
-        }
+    pub fn rotate_around_own_axis(&mut self, angle_rad: f32) {
+        self.angle += angle_rad;
     }
 
+    // turns the slice together with the rest of the cube, around the view axis
     pub fn rotate(&mut self, axis: Axis, angle: f32) {
-        let rotate_fn: Box<dyn Fn(Point3D) -> Point3D> = match axis {
-            Axis::X => Box::new(move |p| p.rotate_x(angle)),
-            Axis::Y => Box::new(move |p| p.rotate_y(angle)),
-            Axis::Z => Box::new(move |p| p.rotate_z(angle)),
+        let rotation = match axis {
+            Axis::X => Matrix3::rotation_x(angle),
+            Axis::Y => Matrix3::rotation_y(angle),
+            Axis::Z => Matrix3::rotation_z(angle),
         };
-
-        let flipped_offset = self.global_cube_position.scalar_multiply(-1.0);
-        for p in &mut self.face_1.corners {
-            *p = p.translate(flipped_offset);
-            *p = rotate_fn(*p);
-            *p = p.translate(self.global_cube_position);
-        }
-        for p in &mut self.face_1.markers {
-            *p = p.translate(flipped_offset);
-            *p = rotate_fn(*p);
-            *p = p.translate(self.global_cube_position);
-        }
-        
-        for p in &mut self.face_2.corners {
-            *p = p.translate(flipped_offset);
-            *p = rotate_fn(*p);
-            *p = p.translate(self.global_cube_position);
-        }
-        for p in &mut self.face_2.markers {
-            *p = p.translate(flipped_offset);
-            *p = rotate_fn(*p);
-            *p = p.translate(self.global_cube_position);
-        }
-        
-        for slice in &mut self.face_slices {
-            for p in &mut slice.corners {
-                *p = p.translate(flipped_offset);
-                *p = rotate_fn(*p);
-                *p = p.translate(self.global_cube_position);
-            }
-            for p in &mut slice.markers {
-                *p = p.translate(flipped_offset);
-                *p = rotate_fn(*p);
-                *p = p.translate(self.global_cube_position);
-            }
-        }
+        self.cube_transform.rotation = rotation.multiply(&self.cube_transform.rotation).orthonormalize();
     }
 }
 
 impl Renderable for CubeSlice {
-    fn get_visible_faces(&self) -> Vec<AnyFace> {
-        let mut faces = vec![
-            AnyFace::Face(self.face_1.clone()),
-            AnyFace::Face(self.face_2.clone()),
-        ];
-
-        faces.extend(self.face_slices.iter()
-            .cloned()
-            .map(AnyFace::FaceSlice));
+    fn visit_faces(&self, visit: &mut dyn FnMut(AnyFace)) {
+        // the faces cut through the cube are only seen while a layer turns
+        let (face_1, face_2) = (&self.face_1, &self.face_2);
+        let [first, second] = match self.order {
+            CubeSliceOrder::FIRST => [AnyFace::Face(face_1), AnyFace::Inside(face_2)],
+            CubeSliceOrder::MIDDLE => [AnyFace::Inside(face_1), AnyFace::Inside(face_2)],
+            CubeSliceOrder::LAST => [AnyFace::Inside(face_1), AnyFace::Face(face_2)],
+        };
+        visit(first);
+        visit(second);
 
-        faces.sort_by(|a, b| a.avg_z().partial_cmp(&b.avg_z()).unwrap());
-        faces
+        for face_slice in &self.face_slices {
+            visit(AnyFace::FaceSlice(face_slice));
+        }
     }
 
-    fn dist(&self) -> f32 {
-        let mut sum = 0.0;
-        
-        sum += self.face_1.avg_z();
-        sum += self.face_2.avg_z();
-
-        for fs in &self.face_slices {
-            sum += fs.avg_z();
-            sum += fs.avg_y() * TIEBREAKER_WEIGHT; // secret sauce
-            sum += fs.avg_x() * TIEBREAKER_WEIGHT; // secret sauce
+    fn transform(&self) -> Transform {
+        Transform {
+            rotation: self.cube_transform.rotation.multiply(&Matrix3::rotation_around(self.axis, self.angle)),
+            position: self.cube_transform.position,
         }
+    }
 
-        sum / 6.0
+    fn center(&self) -> Point3D {
+        self.face_1.center().add(&self.face_2.center()).scalar_multiply(0.5)
     }
 }
\ No newline at end of file
@@ -8,7 +8,8 @@ use crate::{
         core::{
             grid::{Grid, GridFace, GridSide, NeighborSlice}
         }
-    }
+    },
+    game::render::Renderable
 };
 
 pub struct CubeSliceBuilder<'a> {
@@ -66,7 +67,7 @@ impl <'a> CubeSliceBuilder<'a> {
             },
         };
 
-        builder.build_cube_slices(grid)
+        builder.build_cube_slices(&cube.shown(grid))
     }
 
     fn build_cube_slices(self, grid: &Grid) -> [CubeSlice; 3] {
@@ -98,9 +99,9 @@ impl <'a> CubeSliceBuilder<'a> {
         let neighbors_2 = grid.get_neighbors(self.face_2);
         let neighbors_2_colors: Vec<[Color; 3]> = self.get_slices_colors(neighbors_2, grid);
 
-        [
+        let mut slices = [
             CubeSlice::new(
-                self.cube.position,
+                self.cube.transform(),
                 self.cube.faces[f_1_idx].clone(),
                 Face::new(
                     [
@@ -115,7 +116,7 @@ impl <'a> CubeSliceBuilder<'a> {
                 CubeSliceOrder::FIRST
             ),
             CubeSlice::new(
-                self.cube.position,
+                self.cube.transform(),
                 Face::new(
                     [
                         self.cube.faces[sf_0_idx].markers.get(self.idx_1.1).unwrap().clone(),
@@ -136,14 +137,16 @@ impl <'a> CubeSliceBuilder<'a> {
                 CubeSliceOrder::MIDDLE
             ),
             CubeSlice::new(
-                self.cube.position,
+                self.cube.transform(),
                 Face::new(last_corners, GridFace::empty()),
                 self.cube.faces[f_2_idx].clone(),
                 neighbors_2_colors,
                 axis,
                 CubeSliceOrder::LAST
             ),
-        ]
+        ];
+        slices[1].carry_center_marks(&self.cube.faces);
+        slices
     }
 
     fn get_slices_colors(&self, slices: [NeighborSlice; 4], grid: &Grid) -> Vec<[Color; 3]> {
@@ -0,0 +1,288 @@
+use std::f32::consts::FRAC_PI_6;
+
+use crate::{
+    cube::core::{
+        grid::{Grid, GridSide},
+        sticker::{StickerGrid, StickerPos},
+    },
+    utils::{color_scheme::Palette, cube_utils::Color},
+};
+
+// stickers are this many pixels big unless set otherwise
+const STICKER_SIZE: f32 = 30.0;
+// empty space around the diagram, as a part of a sticker
+const MARGIN: f32 = 0.2;
+// lines between the stickers, as a part of a sticker
+const OUTLINE: f32 = 0.06;
+// how thick the side stickers around the top view are, as a part of a sticker
+const SIDE_STRIP: f32 = 0.3;
+// space between the top face and the side stickers around it
+const SIDE_GAP: f32 = 0.1;
+// arrows end this far from the middles of the stickers
+const ARROW_INSET: f32 = 0.25;
+const ARROW_WIDTH: f32 = 0.08;
+
+// corners of a sticker going around it, a sticker being 1 across
+type Polygon = [(f32, f32); 4];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramView {
+    // unfolded like `Grid::print`, the top above the front and the bottom below it
+    Net,
+    // the top, front and right faces seen from the corner between them
+    Cube,
+    // the top face seen from above, with the top stickers of the sides around it
+    TopLayer,
+}
+
+impl DiagramView {
+    // width and height, in stickers
+    fn extent(self) -> (f32, f32) {
+        match self {
+            DiagramView::Net => (12.0, 9.0),
+            DiagramView::Cube => (6.0 * FRAC_PI_6.cos(), 6.0),
+            DiagramView::TopLayer => {
+                let side = 3.0 + 2.0 * (SIDE_STRIP + SIDE_GAP);
+                (side, side)
+            },
+        }
+    }
+
+    fn stickers(self) -> Vec<(StickerPos, Polygon)> {
+        match self {
+            DiagramView::Net => net_stickers(),
+            DiagramView::Cube => cube_stickers(),
+            DiagramView::TopLayer => top_layer_stickers(),
+        }
+    }
+}
+
+// vector drawings of the cube for printed algorithm sheets, like VisualCube
+#[derive(Debug, Clone)]
+pub struct Diagram {
+    view: DiagramView,
+    palette: Palette,
+    sticker_size: f32,
+    // stickers of the top face going from one place to the other
+    arrows: Vec<(StickerPos, StickerPos)>,
+}
+
+impl Diagram {
+    pub fn new(view: DiagramView) -> Diagram {
+        Diagram { view, palette: Palette::default(), sticker_size: STICKER_SIZE, arrows: Vec::new() }
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Diagram {
+        self.palette = palette;
+        self
+    }
+
+    // how many pixels across a sticker is
+    pub fn with_sticker_size(mut self, size: f32) -> Diagram {
+        self.sticker_size = size;
+        self
+    }
+
+    // arrows showing where the permutation takes the stickers of the top face,
+    // like on PLL sheets; two stickers swapping share an arrow with two heads
+    pub fn with_arrows(mut self, permutation: &StickerGrid) -> Diagram {
+        self.arrows = StickerPos::all()
+            .filter(|to| to.side == GridSide::Top)
+            .map(|to| (StickerPos::from_id(permutation.at(to)), to))
+            .filter(|(from, to)| from.side == GridSide::Top && from != to)
+            .collect();
+        self
+    }
+
+    // the diagram as an SVG document
+    pub fn render(&self, grid: &Grid) -> String {
+        let (width, height) = self.view.extent();
+        let size = self.sticker_size;
+        let point = |(x, y): (f32, f32)| ((x + MARGIN) * size, (y + MARGIN) * size);
+        let black = hex(self.palette.rgba(Color::Black));
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.2} {h:.2}\">\n",
+            w = (width + 2.0 * MARGIN) * size,
+            h = (height + 2.0 * MARGIN) * size,
+        );
+
+        let stickers = self.view.stickers();
+        for (pos, polygon) in &stickers {
+            let points: Vec<String> = polygon.iter()
+                .map(|&p| {
+                    let (x, y) = point(p);
+                    format!("{:.2},{:.2}", x, y)
+                })
+                .collect();
+            svg.push_str(&format!(
+                "  <polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{:.2}\" stroke-linejoin=\"round\"/>\n",
+                points.join(" "), hex(self.palette.rgba(grid.at(*pos))), black, OUTLINE * size,
+            ));
+        }
+
+        if !self.arrows.is_empty() {
+            svg.push_str(&format!(
+                "  <defs><marker id=\"head\" viewBox=\"0 0 10 10\" refX=\"5\" refY=\"5\" markerWidth=\"4\" markerHeight=\"4\" orient=\"auto-start-reverse\"><path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{}\"/></marker></defs>\n",
+                black,
+            ));
+        }
+        let middle = |pos: StickerPos| stickers.iter()
+            .find(|(p, _)| *p == pos)
+            .map(|(_, polygon)| {
+                let (x, y) = polygon.iter().fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+                (x / 4.0, y / 4.0)
+            });
+        for &(from, to) in &self.arrows {
+            let swapped = self.arrows.contains(&(to, from));
+            if swapped && from.id() > to.id() {
+                continue;
+            }
+            let (Some(a), Some(b)) = (middle(from), middle(to)) else {
+                continue;
+            };
+
+            let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+            let (dx, dy) = ((b.0 - a.0) / length * ARROW_INSET, (b.1 - a.1) / length * ARROW_INSET);
+            let start = point(if swapped { (a.0 + dx, a.1 + dy) } else { a });
+            let end = point((b.0 - dx, b.1 - dy));
+            svg.push_str(&format!(
+                "  <line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\" stroke-width=\"{:.2}\" stroke-linecap=\"round\"{} marker-end=\"url(#head)\"/>\n",
+                start.0, start.1, end.0, end.1, black, ARROW_WIDTH * size,
+                if swapped { " marker-start=\"url(#head)\"" } else { "" },
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn hex(rgba: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgba[0], rgba[1], rgba[2])
+}
+
+fn square(x: f32, y: f32, width: f32, height: f32) -> Polygon {
+    [(x, y), (x + width, y), (x + width, y + height), (x, y + height)]
+}
+
+fn net_stickers() -> Vec<(StickerPos, Polygon)> {
+    // where each face is in the net, counted in faces
+    let place = |side: GridSide| match side {
+        GridSide::Top => (1, 0),
+        GridSide::Left => (0, 1),
+        GridSide::Front => (1, 1),
+        GridSide::Right => (2, 1),
+        GridSide::Back => (3, 1),
+        _ => (1, 2),
+    };
+
+    StickerPos::all()
+        .map(|pos| {
+            let (x, y) = place(pos.side);
+            let polygon = square((x * 3 + pos.col) as f32, (y * 3 + pos.row) as f32, 1.0, 1.0);
+            (pos, polygon)
+        })
+        .collect()
+}
+
+fn cube_stickers() -> Vec<(StickerPos, Polygon)> {
+    // x goes to the right, y up and z to the front of a cube 3 across,
+    // seen from its front top right corner
+    let (cos, sin) = (FRAC_PI_6.cos(), FRAC_PI_6.sin());
+    let project = |(x, y, z): (f32, f32, f32)| ((x - z + 3.0) * cos, 3.0 - y + (x + z) * sin);
+
+    let mut stickers = Vec::with_capacity(27);
+    for row in 0..3 {
+        for col in 0..3 {
+            let (r, c) = (row as f32, col as f32);
+            let top = [(c, 3.0, r), (c + 1.0, 3.0, r), (c + 1.0, 3.0, r + 1.0), (c, 3.0, r + 1.0)];
+            let front = [(c, 3.0 - r, 3.0), (c + 1.0, 3.0 - r, 3.0), (c + 1.0, 2.0 - r, 3.0), (c, 2.0 - r, 3.0)];
+            let right = [(3.0, 3.0 - r, 3.0 - c), (3.0, 3.0 - r, 2.0 - c), (3.0, 2.0 - r, 2.0 - c), (3.0, 2.0 - r, 3.0 - c)];
+
+            stickers.push((StickerPos::new(GridSide::Top, row, col), top.map(project)));
+            stickers.push((StickerPos::new(GridSide::Front, row, col), front.map(project)));
+            stickers.push((StickerPos::new(GridSide::Right, row, col), right.map(project)));
+        }
+    }
+    stickers
+}
+
+fn top_layer_stickers() -> Vec<(StickerPos, Polygon)> {
+    let top = SIDE_STRIP + SIDE_GAP;
+    let far = top + 3.0 + SIDE_GAP;
+
+    let mut stickers = Vec::with_capacity(21);
+    for row in 0..3 {
+        for col in 0..3 {
+            let polygon = square(top + col as f32, top + row as f32, 1.0, 1.0);
+            stickers.push((StickerPos::new(GridSide::Top, row, col), polygon));
+        }
+    }
+
+    // the top rows of the sides, each sticker next to the one it touches on the top face
+    for i in 0..3 {
+        let along = top + i as f32;
+        stickers.push((StickerPos::new(GridSide::Back, 0, 2 - i), square(along, 0.0, 1.0, SIDE_STRIP)));
+        stickers.push((StickerPos::new(GridSide::Front, 0, i), square(along, far, 1.0, SIDE_STRIP)));
+        stickers.push((StickerPos::new(GridSide::Left, 0, i), square(0.0, along, SIDE_STRIP, 1.0)));
+        stickers.push((StickerPos::new(GridSide::Right, 0, 2 - i), square(far, along, SIDE_STRIP, 1.0)));
+    }
+    stickers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::algorithm::parse_algorithm;
+
+    fn count(svg: &str, pattern: &str) -> usize {
+        svg.matches(pattern).count()
+    }
+
+    fn fill(color: Color) -> String {
+        format!("fill=\"{}\"", hex(Palette::default().rgba(color)))
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::new();
+
+        let net = Diagram::new(DiagramView::Net).render(&grid);
+        assert_eq!(count(&net, "<polygon"), 54);
+        assert_eq!(count(&net, &fill(Color::Yellow)), 9);
+
+        let cube = Diagram::new(DiagramView::Cube).render(&grid);
+        assert_eq!(count(&cube, "<polygon"), 27);
+        assert_eq!(count(&cube, &fill(Color::Yellow)), 0);
+
+        // after U the front shows what was on the right
+        let mut grid = Grid::new();
+        grid.apply_moves(&parse_algorithm("U").unwrap());
+        let top = DiagramView::TopLayer;
+        let stickers = top.stickers();
+        assert_eq!(stickers.len(), 21);
+        let front_strip = stickers.iter()
+            .filter(|(_, polygon)| polygon[0].1 > 3.0)
+            .map(|(pos, _)| grid.at(*pos));
+        assert!(front_strip.into_iter().all(|color| color == grid.at(StickerPos::new(GridSide::Right, 1, 1))));
+    }
+
+    #[test]
+    fn test_arrows() {
+        let arrows = |algorithm: &str| {
+            let permutation = StickerGrid::from_moves(&parse_algorithm(algorithm).unwrap());
+            Diagram::new(DiagramView::TopLayer).with_arrows(&permutation).render(&Grid::new())
+        };
+
+        assert_eq!(count(&arrows(""), "<line"), 0);
+        let u = arrows("U");
+        assert_eq!((count(&u, "<line"), count(&u, "marker-start")), (8, 0));
+
+        // three edges going around, and two pairs of edges swapping
+        let ua = arrows("R U' R U R U R U' R' U' R2");
+        assert_eq!((count(&ua, "<line"), count(&ua, "marker-start")), (3, 0));
+        let h = arrows("M2 U M2 U2 M2 U M2");
+        assert_eq!((count(&h, "<line"), count(&h, "marker-start")), (2, 2));
+    }
+}
@@ -1 +1,3 @@
-pub mod render;
\ No newline at end of file
+pub mod render;
+pub mod snapshot;
+pub mod diagram;
\ No newline at end of file
@@ -1,9 +1,13 @@
+use std::f32::consts::{FRAC_PI_2, PI};
 use std::io::{self, Write};
+use std::ops::Deref;
+use std::rc::Rc;
 
 use crate::{
     utils::{
+        color_scheme::Palette,
         cube_utils::Color,
-        geometry::{Point2D, Point3D, Triangle}
+        geometry::{Point2D, Point3D, Transform}
     },
     cube::{
         slice::FaceSlice,
@@ -14,30 +18,182 @@ use crate::{
 const PRINT_CHAR: &str = "██";
 const ANSI_RESET: &str = "\x1b[0m";
 
-pub enum AnyFace {
-    Face(Face),
-    FaceSlice(FaceSlice),
+pub enum AnyFace<'a> {
+    Face(&'a Face),
+    // cut through the cube, between two of its layers
+    Inside(&'a Face),
+    FaceSlice(&'a FaceSlice),
 }
 
-impl AnyFace {
-    pub fn avg_z(&self) -> f32 {
-        match self {
-            AnyFace::Face(f) => f.avg_z(),
-            AnyFace::FaceSlice(fs) => fs.avg_z(),
+pub trait Renderable {
+    // hands each face in model space to `visit`, in any order
+    fn visit_faces(&self, visit: &mut dyn FnMut(AnyFace));
+
+    // where the model is in the view, applied only when projecting
+    fn transform(&self) -> Transform;
+
+    // middle of the model, telling the outside of its faces from the inside
+    fn center(&self) -> Point3D;
+}
+
+// nothing nearer to the eye than this gets drawn, what crosses it is cut off
+const NEAR_PLANE: f32 = 0.05;
+// projected points far off the screen are kept this far out at most
+const GUARD_BAND: f32 = 1e6;
+// corners are snapped to this many steps across a sample before filling
+const SUBSAMPLES: f32 = 256.0;
+
+// pushes stickers in front of the plastic under them
+const STICKER_DEPTH_BIAS: f32 = 5e-5;
+// pushes marks drawn on a sticker in front of it
+const MARK_DEPTH_BIAS: f32 = 1e-4;
+// points along each rounded corner of a sticker
+const CORNER_STEPS: usize = 4;
+// pushes faces inside the cube behind the stickers they touch at the edges
+const INSIDE_DEPTH_BIAS: DepthBias = DepthBias { depth: -1e-4, samples: -2.0 };
+
+// how stickers sit on the cube; with no gap they cover it edge to edge
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StickerStyle {
+    // where the plastic shows around each sticker, as a part of its cell
+    pub gap: f32,
+    // how round the corners are, from square at 0 to a circle at 1
+    pub rounding: f32,
+}
+
+impl StickerStyle {
+    // the sticker within its cell as (row, col) points going around it,
+    // none when it covers the whole cell
+    fn outline(&self) -> Option<Vec<(f32, f32)>> {
+        if self.gap <= 0.0 {
+            return None;
+        }
+
+        let lo = self.gap.min(0.9) / 2.0;
+        let hi = 1.0 - lo;
+        let radius = self.rounding.clamp(0.0, 1.0) * (hi - lo) / 2.0;
+        if radius == 0.0 {
+            return Some(vec![(lo, lo), (hi, lo), (hi, hi), (lo, hi)]);
         }
+
+        // middles of the corner arcs, each with the angle where its arc starts
+        let arcs = [
+            (lo + radius, lo + radius, PI),
+            (hi - radius, lo + radius, PI + FRAC_PI_2),
+            (hi - radius, hi - radius, 0.0),
+            (lo + radius, hi - radius, FRAC_PI_2),
+        ];
+        let outline = arcs.iter()
+            .flat_map(|&(row, col, start)| (0..=CORNER_STEPS).map(move |step| {
+                let angle = start + FRAC_PI_2 * step as f32 / CORNER_STEPS as f32;
+                (row + radius * angle.cos(), col + radius * angle.sin())
+            }))
+            .collect();
+        Some(outline)
     }
 }
 
-pub trait Renderable {
-    fn get_visible_faces(&self) -> Vec<AnyFace>;
+// a light shining from far away in one direction, like the sun
+#[derive(Debug, Clone, Copy)]
+pub struct Lighting {
+    // towards the light, as seen from the eye looking along z
+    pub direction: Point3D,
+    // how bright faces turned away from the light still are, from 0 to 1
+    pub ambient: f32,
+}
+
+impl Lighting {
+    // brightness of a face with the given normal, from the ambient light up to 1
+    fn shade(&self, normal: Point3D) -> f32 {
+        let diffuse = normal.dot(&self.direction.normalize()).max(0.0);
+        self.ambient + (1.0 - self.ambient) * diffuse
+    }
+}
+
+impl Default for Lighting {
+    // from above, on the left and behind the eye
+    fn default() -> Lighting {
+        Lighting {
+            direction: Point3D { x: -0.4, y: 0.7, z: -1.0 },
+            ambient: 0.5,
+        }
+    }
+}
+
+// how far a triangle is pushed towards the eye: by some depth, and by how much its
+// own depth changes across some samples, which snapping its corners can be off by
+#[derive(Debug, Clone, Copy, Default)]
+struct DepthBias {
+    depth: f32,
+    samples: f32,
+}
+
+impl DepthBias {
+    fn nudged(self, depth: f32) -> DepthBias {
+        DepthBias { depth: self.depth + depth, ..self }
+    }
+}
 
-    fn dist(&self) -> f32;
+// a point projected on the screen, exactly where it lands, with 1 / z for the depth
+#[derive(Debug, Clone, Copy)]
+struct Projected {
+    x: f32,
+    y: f32,
+    depth: f32,
+}
+
+impl Projected {
+    // the point snapped to whole subsamples, for filling triangles exactly
+    fn snapped(&self) -> Point2D {
+        Point2D { x: (self.x * SUBSAMPLES).round() as isize, y: (self.y * SUBSAMPLES).round() as isize }
+    }
+
+    // like `edge`, at the exact places
+    fn edge_to(&self, b: &Projected, p: &Projected) -> f32 {
+        (b.x - self.x) * (p.y - self.y) - (b.y - self.y) * (p.x - self.x)
+    }
+}
+
+// a sticker's place on a face, from its corners going around it
+struct Cell([Point3D; 4]);
+
+impl Cell {
+    // the point at (row, col), from (0, 0) at the first corner to (1, 1) at the third
+    fn at(&self, row: f32, col: f32) -> Point3D {
+        let [corner, next_col, _, next_row] = self.0;
+        corner
+            .add(&next_row.subtract(&corner).scalar_multiply(row))
+            .add(&next_col.subtract(&corner).scalar_multiply(col))
+    }
+}
+
+// the part of a triangle in front of the near plane, up to four corners
+struct Clipped {
+    points: [Point3D; 4],
+    len: usize,
+}
+
+impl Deref for Clipped {
+    type Target = [Point3D];
+
+    fn deref(&self) -> &[Point3D] {
+        &self.points[..self.len]
+    }
 }
 
+// the buffers hold samples row by row, from the bottom of the screen up
 pub struct Screen {
     size_x: usize,
     size_y: usize,
-    screen: Vec<Vec<Option<Color>>>,
+    // each pixel is drawn as samples x samples smaller ones, blended in `rgba_at`
+    samples: usize,
+    screen: Vec<Option<Color>>,
+    // 1 / z of what is drawn at each sample, bigger is nearer
+    depth: Vec<f32>,
+    // how brightly lit what is drawn at each sample is
+    shade: Vec<f32>,
+    sticker_outline: Option<Rc<[(f32, f32)]>>,
+    lighting: Option<Lighting>,
     zp: f32,
     projection_scale: f32,
 }
@@ -47,151 +203,284 @@ impl Screen {
         Screen {
             size_x,
             size_y,
-            screen: vec![vec![None; size_x]; size_y],
+            samples: 1,
+            screen: vec![None; size_x * size_y],
+            depth: vec![0.0; size_x * size_y],
+            shade: vec![1.0; size_x * size_y],
+            sticker_outline: None,
+            lighting: None,
             zp,
             projection_scale,
         }
     }
 
+    // smooths the edges by drawing every pixel from samples x samples smaller ones
+    pub fn supersampled(mut self, samples: usize) -> Screen {
+        self.samples = samples.max(1);
+        let len = self.size_x * self.size_y * self.samples * self.samples;
+        self.screen = vec![None; len];
+        self.depth = vec![0.0; len];
+        self.shade = vec![1.0; len];
+        self
+    }
+
+    pub fn with_stickers(mut self, stickers: StickerStyle) -> Screen {
+        self.sticker_outline = stickers.outline().map(Rc::from);
+        self
+    }
+
+    // shades the faces by how they are turned to the light, so that faces
+    // of similar colors next to each other are easier to tell apart
+    pub fn with_lighting(mut self, lighting: Option<Lighting>) -> Screen {
+        self.lighting = lighting;
+        self
+    }
+
+    // samples in one row of the buffers
+    fn row_len(&self) -> usize {
+        self.size_x * self.samples
+    }
+
+    // the color in the middle of the pixel, counting y from the top
     pub fn color_at(&self, x: i16, y: i16) -> Option<Color> {
-        self.screen[self.size_y - y as usize - 1][x as usize]
+        let row = (self.size_y - y as usize - 1) * self.samples + self.samples / 2;
+        self.screen[row * self.row_len() + x as usize * self.samples + self.samples / 2]
+    }
+
+    // the pixel blended from all of its samples, as much opaque as it is covered
+    pub fn rgba_at(&self, x: i16, y: i16, palette: &Palette) -> [u8; 4] {
+        self.blended(x as usize, self.size_y - y as usize - 1, palette)
+    }
+
+    // writes the whole screen into an RGBA frame, top row first, laid over
+    // the black of the palette where the cube doesn't cover it
+    pub fn draw_into(&self, frame: &mut [u8], palette: &Palette) {
+        // a screen without any pixels has nothing to draw
+        if self.size_x == 0 {
+            return;
+        }
+        let background = palette.rgba(Color::Black);
+        for (y, frame_row) in frame.chunks_exact_mut(self.size_x * 4).take(self.size_y).enumerate() {
+            let row = self.size_y - y - 1;
+            for (x, pixel) in frame_row.chunks_exact_mut(4).enumerate() {
+                pixel.copy_from_slice(&blend(self.blended(x, row, palette), background));
+            }
+        }
+    }
+
+    // the pixel at (col, row), counting rows from the bottom like the buffers
+    fn blended(&self, col: usize, row: usize, palette: &Palette) -> [u8; 4] {
+        let row_len = self.row_len();
+        let first = row * self.samples * row_len + col * self.samples;
+
+        let mut sum = [0u32; 3];
+        let mut covered = 0u32;
+        for start in (0..self.samples).map(|i| first + i * row_len) {
+            let samples = start..start + self.samples;
+            for (color, shade) in self.screen[samples.clone()].iter().zip(&self.shade[samples]) {
+                let Some(color) = color else {
+                    continue;
+                };
+                let rgba = palette.rgba(*color);
+                for (channel, value) in sum.iter_mut().zip(rgba) {
+                    *channel += (value as f32 * shade).round() as u32;
+                }
+                covered += 1;
+            }
+        }
+
+        if covered == 0 {
+            return [0, 0, 0, 0];
+        }
+        let total = (self.samples * self.samples) as u32;
+        let [r, g, b] = sum.map(|channel| ((channel + covered / 2) / covered) as u8);
+        [r, g, b, ((covered * 255 + total / 2) / total) as u8]
     }
 
-    fn project_point(&self, p: Point3D) -> Point2D {
-        let screen_x_offset = (self.size_x / 2) as isize;
-        let screen_y_offset = (self.size_y / 2) as isize;
+    // the point on the screen, with 1 / z for the depth;
+    // the point has to be in front of the near plane
+    fn project_point(&self, p: Point3D) -> Projected {
+        let screen_x_offset = (self.size_x / 2 * self.samples) as f32;
+        let screen_y_offset = (self.size_y / 2 * self.samples) as f32;
 
         let multiplier = self.zp / p.z;
         let xp = p.x * multiplier;
         let yp = p.y * multiplier;
+        let scale = self.projection_scale * self.samples as f32;
 
-        let x_proj = (xp * self.projection_scale) as isize + screen_x_offset;
-        let y_proj = (yp * self.projection_scale) as isize + screen_y_offset;
-
-        Point2D {x: x_proj, y: y_proj}
+        Projected {
+            x: (xp * scale).clamp(-GUARD_BAND, GUARD_BAND) + screen_x_offset,
+            y: (yp * scale).clamp(-GUARD_BAND, GUARD_BAND) + screen_y_offset,
+            depth: 1.0 / p.z,
+        }
     }
 
-    // uses the "scanline" method
-    fn rasterize_triangle(&mut self, tri: Triangle, color: Color) {
-        let mut pts = [tri.0, tri.1, tri.2];
-        // sort vertices by y (top to bottom)
-        pts.sort_by_key(|p| p.y);
-
-        let (v1, v2, v3) = (pts[0], pts[1], pts[2]);
+    // fills the pixels inside the triangle; 1 / z changes linearly across the
+    // screen, so it is interpolated from the corners to keep only the nearest pixels
+    fn rasterize_triangle(&mut self, corners: [Projected; 3], bias: DepthBias, color: Color, shade: f32) {
+        let [p1, p2, p3] = corners;
+        let (mut v1, mut v2, v3) = (p1.snapped(), p2.snapped(), p3.snapped());
 
-        // avoid dividing by zero
-        let inv_slope_1 = if v2.y != v1.y {
-            (v2.x - v1.x) as f32 / (v2.y - v1.y) as f32
-        } else { 0.0 };
+        // the corners go the same way around, so inside is where all edges agree
+        let area = edge(v1, v2, v3);
+        if area == 0 {
+            return;
+        }
+        if area < 0 {
+            (v1, v2) = (v2, v1);
+        }
 
-        let inv_slope_2 = if v3.y != v1.y {
-            (v3.x - v1.x) as f32 / (v3.y - v1.y) as f32
-        } else { 0.0 };
+        // the depth comes from the exact corners rather than the snapped ones, and
+        // samples just outside the exact triangle get the depth at its nearest edge,
+        // so faces meeting at an edge never reach over each other
+        let exact_area = p1.edge_to(&p2, &p3);
+        let slope = if exact_area.is_normal() {
+            let ddx = ((p2.depth - p1.depth) * (p3.y - p1.y) - (p3.depth - p1.depth) * (p2.y - p1.y)) / exact_area;
+            let ddy = ((p3.depth - p1.depth) * (p2.x - p1.x) - (p2.depth - p1.depth) * (p3.x - p1.x)) / exact_area;
+            ddx.abs().max(ddy.abs())
+        } else {
+            0.0
+        };
+        let offset = bias.depth + bias.samples * slope;
+        let depth_at = |x: isize, y: isize| -> f32 {
+            let p = Projected { x: x as f32, y: y as f32, depth: 0.0 };
+            let weights = [p2.edge_to(&p3, &p), p3.edge_to(&p1, &p), p1.edge_to(&p2, &p)]
+                .map(|w| (w / exact_area).max(0.0));
+            let total: f32 = weights.iter().sum();
+            if !exact_area.is_normal() || total <= 0.0 {
+                return p1.depth.max(p2.depth).max(p3.depth) + offset;
+            }
+            (weights[0] * p1.depth + weights[1] * p2.depth + weights[2] * p3.depth) / total + offset
+        };
 
-        let inv_slope_3 = if v3.y != v2.y {
-            (v3.x - v2.x) as f32 / (v3.y - v2.y) as f32
-        } else { 0.0 };
+        let step = SUBSAMPLES as isize;
+        let x_min = v1.x.min(v2.x).min(v3.x).div_euclid(step).max(0);
+        let x_max = v1.x.max(v2.x).max(v3.x).div_euclid(step).min((self.size_x * self.samples) as isize - 1);
+        let y_min = v1.y.min(v2.y).min(v3.y).div_euclid(step).max(0);
+        let y_max = v1.y.max(v2.y).max(v3.y).div_euclid(step).min((self.size_y * self.samples) as isize - 1);
+        let row_len = self.row_len();
 
-        // top half (v1 → v2 and v1 → v3)
-        let mut curx1 = v1.x as f32;
-        let mut curx2 = v1.x as f32;
-        for y in v1.y..=v2.y {
-            if y >= 0 && y < self.size_y as isize {
-                let x_start = curx1.min(curx2).max(0.0) as isize;
-                let x_end   = curx1.max(curx2).min(self.size_x as f32 - 1.0) as isize;
-                for x in x_start..=x_end {
-                    self.screen[y as usize][x as usize] = Some(color);
+        for y in y_min..=y_max {
+            for x in x_min..=x_max {
+                let p = Point2D { x: x * step, y: y * step };
+                if !covers(edge(v2, v3, p), v2, v3) || !covers(edge(v3, v1, p), v3, v1) || !covers(edge(v1, v2, p), v1, v2) {
+                    continue;
                 }
-            }
-            curx1 += inv_slope_1;
-            curx2 += inv_slope_2;
-        }
-
-        // bottom half (v2 → v3 and v1 → v3)
-        let mut curx1 = v2.x as f32;
-        let mut curx2 = v1.x as f32 + inv_slope_2 * (v2.y - v1.y) as f32;
-        for y in v2.y..=v3.y {
-            if y >= 0 && y < self.size_y as isize {
-                let x_start = curx1.min(curx2).max(0.0) as isize;
-                let x_end   = curx1.max(curx2).min(self.size_x as f32 - 1.0) as isize;
-                for x in x_start..=x_end {
-                    self.screen[y as usize][x as usize] = Some(color);
+
+                let depth = depth_at(x, y);
+                let i = y as usize * row_len + x as usize;
+                if depth > self.depth[i] {
+                    self.depth[i] = depth;
+                    self.screen[i] = Some(color);
+                    self.shade[i] = shade;
                 }
             }
-            curx1 += inv_slope_3;
-            curx2 += inv_slope_2;
         }
     }
 
+    // fills the part of the triangle in front of the near plane
+    fn rasterize_clipped(&mut self, tri: [Point3D; 3], bias: DepthBias, color: Color) {
+        let shade = self.lighting.map_or(1.0, |lighting| lighting.shade(facing_normal(tri)));
+        let clipped = clip_near(tri);
+        let projected = clipped.points.map(|p| self.project_point(p));
 
-    fn render_face(&mut self, face: &Face) {
-        let projected_markers: Vec<Point2D> = face.markers
-            .iter()
-            .map(|&p| self.project_point(p))
-            .collect();
+        for i in 1..clipped.len().saturating_sub(1) {
+            self.rasterize_triangle([projected[0], projected[i], projected[i + 1]], bias, color, shade);
+        }
+    }
+
+    // a sticker with the plastic around it, or the plain sticker filling the cell
+    fn render_cell(&mut self, cell: Cell, color: Color, bias: DepthBias) {
+        let [a, b, c, d] = cell.0;
+        let Some(outline) = self.sticker_outline.clone() else {
+            self.rasterize_clipped([a, b, c], bias, color);
+            self.rasterize_clipped([a, c, d], bias, color);
+            return;
+        };
+
+        let middle = cell.at(0.5, 0.5);
+        self.rasterize_clipped([a, b, c], bias, Color::Plastic);
+        self.rasterize_clipped([a, c, d], bias, Color::Plastic);
+        for i in 0..outline.len() {
+            let ((row, col), (next_row, next_col)) = (outline[i], outline[(i + 1) % outline.len()]);
+            let edge = [middle, cell.at(row, col), cell.at(next_row, next_col)];
+            self.rasterize_clipped(edge, bias.nudged(STICKER_DEPTH_BIAS), color);
+        }
+    }
+
+    fn render_face(&mut self, face: &Face, transform: &Transform, bias: DepthBias) {
+        let markers: [Point3D; 16] = placed(&face.markers, transform);
 
         for row in 0..3 {
             for col in 0..3 {
-                let tris = [
-                    Triangle(projected_markers.get(row * 4 + col).unwrap().clone(), 
-                        projected_markers.get(row * 4 + col + 1).unwrap().clone(), 
-                        projected_markers.get((row + 1) * 4 + col + 1).unwrap().clone()
-                    ),
-                    Triangle(projected_markers.get(row * 4 + col).unwrap().clone(), 
-                        projected_markers.get((row + 1) * 4 + col + 1).unwrap().clone(),
-                        projected_markers.get((row + 1) * 4 + col).unwrap().clone()
-                    ),
-                ];
                 let color = face.grid_face.grid[row][col];
-                for tri in tris {
-                    self.rasterize_triangle(tri, color);
-                }
+                let corner = row * 4 + col;
+                let cell = Cell([markers[corner], markers[corner + 1], markers[corner + 5], markers[corner + 4]]);
+                self.render_cell(cell, color, bias);
             }
         }
+
+        if let Some(mark) = face.center_mark_triangle() {
+            self.rasterize_clipped(mark.map(|p| transform.apply(p)), bias.nudged(MARK_DEPTH_BIAS), Color::Black);
+        }
     }
 
-    fn render_face_slice(&mut self, face_slice: &FaceSlice) {
-        let projected_markers: Vec<Point2D> = face_slice.markers
-            .iter()
-            .map(|&p| self.project_point(p))
-            .collect();
+    // with stickers on the plastic, the inside of the cube is plain plastic too
+    fn render_inside(&mut self, face: &Face, transform: &Transform) {
+        if self.sticker_outline.is_none() {
+            self.render_face(face, transform, INSIDE_DEPTH_BIAS);
+            return;
+        }
+
+        let [a, b, c, d] = face.corners.map(|p| transform.apply(p));
+        self.rasterize_clipped([a, b, c], INSIDE_DEPTH_BIAS, Color::Plastic);
+        self.rasterize_clipped([a, c, d], INSIDE_DEPTH_BIAS, Color::Plastic);
+    }
+
+    fn render_face_slice(&mut self, face_slice: &FaceSlice, transform: &Transform) {
+        let markers: [Point3D; 8] = placed(&face_slice.markers, transform);
 
         for row in 0..3 {
-            let tris = [
-                Triangle(projected_markers.get(row * 2).unwrap().clone(), 
-                    projected_markers.get(row * 2 + 1).unwrap().clone(), 
-                    projected_markers.get((row + 1) * 2 + 1).unwrap().clone()
-                ),
-                Triangle(projected_markers.get(row * 2).unwrap().clone(), 
-                    projected_markers.get((row + 1) * 2 + 1).unwrap().clone(),
-                    projected_markers.get((row + 1) * 2).unwrap().clone()
-                ),
-            ];
             let color = face_slice.colors[row];
-            for tri in tris {
-                self.rasterize_triangle(tri, color);
-            }
+            let corner = row * 2;
+            let cell = Cell([markers[corner], markers[corner + 1], markers[corner + 3], markers[corner + 2]]);
+            self.render_cell(cell, color, DepthBias::default());
+        }
+
+        if let Some(mark) = face_slice.center_mark {
+            let bias = DepthBias::default().nudged(MARK_DEPTH_BIAS);
+            self.rasterize_clipped(mark.map(|p| transform.apply(p)), bias, Color::Black);
         }
     }
 
-    pub fn render(&mut self, mut renderables: Vec<&dyn Renderable>) {
-        renderables.sort_by(|a, b| a.dist().partial_cmp(&b.dist()).unwrap());
+    // the depth buffer keeps the nearest faces, so they can be drawn in any order
+    pub fn render(&mut self, renderables: &[&dyn Renderable]) {
+        for renderable in renderables {
+            let transform = renderable.transform();
+            let center = transform.apply(renderable.center());
+            renderable.visit_faces(&mut |face| {
+                let markers = match &face {
+                    AnyFace::Face(f) | AnyFace::Inside(f) => &f.markers,
+                    AnyFace::FaceSlice(fs) => &fs.markers,
+                };
+                if !is_facing_camera(markers, &transform, center) {
+                    return;
+                }
 
-        for renderable in renderables.into_iter().rev() {
-            let faces = renderable.get_visible_faces();
-            for face in faces.into_iter().take(3).rev() {
                 match face {
-                    AnyFace::Face(f) => self.render_face(&f),
-                    AnyFace::FaceSlice(fs) => self.render_face_slice(&fs),
+                    AnyFace::Face(f) => self.render_face(f, &transform, DepthBias::default()),
+                    AnyFace::Inside(f) => self.render_inside(f, &transform),
+                    AnyFace::FaceSlice(fs) => self.render_face_slice(fs, &transform),
                 };
-            }
+            });
         }
     }
 
     pub fn print_screen(&self) {
         for y in (0..(self.size_y)).rev() {
             for x in 0..(self.size_x) {
-                match self.screen[y][x] {
+                let middle = self.samples / 2;
+                match self.screen[(y * self.samples + middle) * self.row_len() + x * self.samples + middle] {
                     Some(color) => print!("{}{}{}", color.to_ansi(), PRINT_CHAR, ANSI_RESET),
                     _ => print!("  ")
                 };
@@ -207,10 +496,303 @@ impl Screen {
     }
 
     pub fn clear_screen(&mut self) {
-        for row in self.screen.iter_mut() {
-            for cell in row.iter_mut() {
-                *cell = None;
+        self.screen.fill(None);
+        self.depth.fill(0.0);
+    }
+}
+
+// the part of the triangle in front of the near plane, as a polygon of up to four corners
+fn clip_near(tri: [Point3D; 3]) -> Clipped {
+    let mut clipped = Clipped { points: [tri[0]; 4], len: 0 };
+    let mut push = |p: Point3D| {
+        clipped.points[clipped.len] = p;
+        clipped.len += 1;
+    };
+    for i in 0..3 {
+        let (a, b) = (tri[i], tri[(i + 1) % 3]);
+        let (a_in, b_in) = (a.z >= NEAR_PLANE, b.z >= NEAR_PLANE);
+        if a_in {
+            push(a);
+        }
+        if a_in != b_in {
+            let t = (NEAR_PLANE - a.z) / (b.z - a.z);
+            push(a.add(&b.subtract(&a).scalar_multiply(t)));
+        }
+    }
+    clipped
+}
+
+// model points placed in the view, without allocating
+fn placed<const N: usize>(points: &[Point3D], transform: &Transform) -> [Point3D; N] {
+    std::array::from_fn(|i| transform.apply(points[i]))
+}
+
+// lays a pixel only partly covered by the cube over the background
+fn blend(rgba: [u8; 4], background: [u8; 4]) -> [u8; 4] {
+    let alpha = rgba[3] as u32;
+    let mut blended = [0, 0, 0, 255];
+    for i in 0..3 {
+        blended[i] = ((rgba[i] as u32 * alpha + background[i] as u32 * (255 - alpha) + 127) / 255) as u8;
+    }
+    blended
+}
+
+// normal of the triangle on the side the eye sees it from
+fn facing_normal([a, b, c]: [Point3D; 3]) -> Point3D {
+    let normal = b.subtract(&a).cross(&c.subtract(&a));
+    // the eye sits at the origin
+    if normal.dot(&a) > 0.0 {
+        normal.scalar_multiply(-1.0).normalize()
+    } else {
+        normal.normalize()
+    }
+}
+
+// twice the area of the triangle a, b, p; positive when p is to the left of a → b
+fn edge(a: Point2D, b: Point2D, p: Point2D) -> i64 {
+    (b.x - a.x) as i64 * (p.y - a.y) as i64 - (b.y - a.y) as i64 * (p.x - a.x) as i64
+}
+
+// pixels right on an edge shared by two triangles go to only one of them,
+// so neighbouring stickers leave no gaps and don't draw over each other
+fn covers(w: i64, a: Point2D, b: Point2D) -> bool {
+    w > 0 || (w == 0 && (b.y > a.y || (b.y == a.y && b.x < a.x)))
+}
+
+// whether the camera is on the outer side of a face, the one away from the
+// middle of its model; faces turned away are hidden anyway, and leaving them
+// out keeps their edges from showing around the outline
+fn is_facing_camera(markers: &[Point3D], transform: &Transform, center: Point3D) -> bool {
+    let sum = markers.iter()
+        .map(|&p| transform.apply(p))
+        .fold(Point3D { x: 0.0, y: 0.0, z: 0.0 }, |sum, p| sum.add(&p));
+    let face_center = sum.scalar_multiply(1.0 / markers.len() as f32);
+
+    // the camera sits at the origin
+    face_center.subtract(&center).dot(&face_center) < 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+    use crate::cube::{
+        cube::Cube,
+        core::{algorithm::parse_algorithm, grid::Grid},
+        slice_builder::CubeSliceBuilder
+    };
+
+    // solved and seen the way the game shows it
+    fn solved_cube() -> Cube {
+        let mut cube = Cube::new((0.0, 0.0, 5.0), FRAC_PI_4, -FRAC_PI_4);
+        cube.apply_grid(&Grid::new());
+        cube
+    }
+
+    fn seen_colors(renderables: Vec<&dyn Renderable>) -> Vec<Color> {
+        let mut screen = Screen::new(40, 40, 6.0, 5.0);
+        screen.render(&renderables);
+        screen.screen.into_iter().flatten().collect()
+    }
+
+    // only the top, front and right of a cube held straight are seen
+    fn only_front_faces(seen: &[Color]) -> bool {
+        !seen.is_empty() && seen.iter().all(|c| matches!(c, Color::White | Color::Green | Color::Red | Color::Black | Color::Plastic))
+    }
+
+    #[test]
+    fn test_hidden_faces_in_any_order() {
+        let cube = solved_cube();
+        let grid = Grid::new();
+        assert!(only_front_faces(&seen_colors(vec![&cube])));
+
+        for notation in ["R", "U", "F'", "M"] {
+            let mv = parse_algorithm(notation).unwrap().remove(0);
+            let mut slices = CubeSliceBuilder::create_cube_slices(&cube, &grid, &mv.axis);
+            let [first, middle, last] = &slices;
+            for order in [[first, middle, last], [last, middle, first], [middle, last, first]] {
+                let seen = seen_colors(order.iter().map(|&s| s as &dyn Renderable).collect());
+                assert!(only_front_faces(&seen), "{}", notation);
             }
+
+            // the inside of the cube shows while a layer turns
+            slices[mv.order.idx()].rotate_around_own_axis(0.6);
+            let seen = seen_colors(slices.iter().map(|s| s as &dyn Renderable).collect());
+            assert!(seen.contains(&Color::Gray), "{}", notation);
+        }
+    }
+
+    #[test]
+    fn test_clip_near() {
+        let p = |x, y, z| Point3D { x, y, z };
+
+        assert_eq!(clip_near([p(0.0, 0.0, 1.0), p(1.0, 0.0, 1.0), p(0.0, 1.0, 1.0)]).len(), 3);
+        assert!(clip_near([p(0.0, 0.0, -1.0), p(1.0, 0.0, -1.0), p(0.0, 1.0, 0.0)]).is_empty());
+
+        // one corner behind the eye leaves four, two leave the tip
+        let clipped = clip_near([p(0.0, 0.0, -1.0), p(1.0, 0.0, 1.0), p(0.0, 1.0, 1.0)]);
+        assert_eq!(clipped.len(), 4);
+        assert!(clipped.iter().all(|p| p.z >= NEAR_PLANE - 1e-6));
+        let clipped = clip_near([p(0.0, 0.0, -1.0), p(1.0, 0.0, -1.0), p(0.0, 1.0, 1.0)]);
+        assert_eq!(clipped.len(), 3);
+        assert!(clipped.iter().all(|p| p.z >= NEAR_PLANE - 1e-6));
+    }
+
+    #[test]
+    fn test_render_close_to_the_eye() {
+        let mut cube = solved_cube();
+
+        // corners close enough to be cut off by the near plane
+        for z in [1.5, 2.0] {
+            cube.position.z = z;
+            assert!(!seen_colors(vec![&cube]).is_empty(), "{}", z);
+        }
+
+        // with the eye inside the cube all faces turn away from it,
+        // and the cube behind the eye is not seen at all
+        for z in [0.0, 0.5, -5.0] {
+            cube.position.z = z;
+            assert!(seen_colors(vec![&cube]).is_empty(), "{}", z);
+        }
+
+        // far off to the side still renders, just nothing on the screen
+        cube.position = Point3D { x: 1e9, y: -1e9, z: 1.0 };
+        assert!(seen_colors(vec![&cube]).is_empty());
+    }
+
+    #[test]
+    fn test_supersampling() {
+        let cube = solved_cube();
+        let palette = Palette::default();
+
+        let alphas = |samples: usize| {
+            let mut screen = Screen::new(40, 40, 6.0, 5.0).supersampled(samples);
+            screen.render(&[&cube]);
+            let mut alphas = Vec::new();
+            for y in 0..40 {
+                for x in 0..40 {
+                    let rgba = screen.rgba_at(x, y, &palette);
+                    if samples == 1 {
+                        let color = screen.color_at(x, y).map(|c| palette.rgba(c));
+                        assert_eq!(rgba, color.unwrap_or([0, 0, 0, 0]));
+                    }
+                    alphas.push(rgba[3]);
+                }
+            }
+            alphas
+        };
+
+        // without it every pixel is either covered or not, with it the edges blend
+        assert!(alphas(1).iter().all(|&a| a == 0 || a == 255));
+        let smooth = alphas(4);
+        assert!(smooth.iter().any(|&a| a > 0 && a < 255));
+        assert!(smooth.iter().filter(|&&a| a == 255).count() > 100);
+    }
+
+    #[test]
+    fn test_draw_into() {
+        let cube = solved_cube();
+        let palette = Palette::default();
+        let mut screen = Screen::new(40, 30, 6.0, 5.0).supersampled(2);
+        screen.render(&[&cube]);
+
+        // the frame goes row by row from the top, like `rgba_at`
+        let mut frame = vec![0; 40 * 30 * 4];
+        screen.draw_into(&mut frame, &palette);
+        let background = palette.rgba(Color::Black);
+        for (i, pixel) in frame.chunks_exact(4).enumerate() {
+            let rgba = screen.rgba_at((i % 40) as i16, (i / 40) as i16, &palette);
+            assert_eq!(pixel, blend(rgba, background));
         }
+        assert_eq!(frame[..4], background);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_lighting() {
+        let cube = solved_cube();
+        let palette = Palette::default();
+
+        let shades = |lighting: Option<Lighting>| {
+            let mut screen = Screen::new(40, 40, 6.0, 5.0).with_lighting(lighting);
+            screen.render(&[&cube]);
+            let mut shades: Vec<f32> = Vec::new();
+            for y in 0..40 {
+                for x in 0..40 {
+                    let Some(color) = screen.color_at(x, y) else {
+                        continue;
+                    };
+                    let rgba = screen.rgba_at(x, y, &palette);
+                    let flat = palette.rgba(color);
+                    assert!(rgba.iter().zip(flat).all(|(&lit, flat)| lit <= flat));
+                    let shade = screen.shade[(39 - y as usize) * 40 + x as usize];
+                    if shades.iter().all(|seen| (seen - shade).abs() > 1e-3) {
+                        shades.push(shade);
+                    }
+                }
+            }
+            shades
+        };
+
+        // each of the three faces in sight is lit differently
+        assert_eq!(shades(None), vec![1.0]);
+        let lit = shades(Some(Lighting::default()));
+        assert_eq!(lit.len(), 3);
+        assert!(lit.iter().all(|&shade| shade >= Lighting::default().ambient && shade <= 1.0));
+    }
+
+    #[test]
+    fn test_sticker_gaps() {
+        let cube = solved_cube();
+        let grid = Grid::new();
+
+        let plastic = |stickers: StickerStyle, renderables: Vec<&dyn Renderable>| {
+            let mut screen = Screen::new(80, 80, 6.0, 10.0).with_stickers(stickers);
+            screen.render(&renderables);
+            let seen: Vec<Color> = screen.screen.into_iter().flatten().collect();
+            assert!(only_front_faces(&seen));
+            seen.iter().filter(|&&c| c == Color::Plastic).count()
+        };
+
+        let flat = plastic(StickerStyle::default(), vec![&cube]);
+        let gaps = plastic(StickerStyle { gap: 0.2, rounding: 0.0 }, vec![&cube]);
+        let rounded = plastic(StickerStyle { gap: 0.2, rounding: 0.8 }, vec![&cube]);
+        assert_eq!(flat, 0);
+        assert!(gaps > 0);
+        assert!(rounded > gaps);
+
+        // layers split for a move look the same, the plastic inside stays hidden
+        let mv = parse_algorithm("U").unwrap().remove(0);
+        let slices = CubeSliceBuilder::create_cube_slices(&cube, &grid, &mv.axis);
+        let split = plastic(StickerStyle { gap: 0.2, rounding: 0.0 }, slices.iter().map(|s| s as &dyn Renderable).collect());
+        assert!(split.abs_diff(gaps) < gaps / 10);
+
+        // the plastic stands out from the background around the cube
+        let palette = Palette::default();
+        assert_ne!(palette.rgba(Color::Plastic), palette.rgba(Color::Black));
+    }
+
+    #[test]
+    fn test_center_marks_on_slices() {
+        let mut cube = solved_cube();
+        cube.show_center_marks(true);
+        let grid = Grid::new();
+        cube.apply_grid(&grid);
+
+        let marks = |renderables: Vec<&dyn Renderable>| {
+            seen_colors(renderables).into_iter().filter(|&c| c == Color::Black).count()
+        };
+        let whole = marks(vec![&cube]);
+        assert!(whole > 0);
+
+        // the middle layer holds the centers of the top and the front
+        let mv = parse_algorithm("M").unwrap().remove(0);
+        let mut slices = CubeSliceBuilder::create_cube_slices(&cube, &grid, &mv.axis);
+        assert_eq!(slices[1].face_slices.iter().filter(|fs| fs.center_mark.is_some()).count(), 4);
+        let split = marks(slices.iter().map(|s| s as &dyn Renderable).collect());
+        assert!(split.abs_diff(whole) <= whole / 10);
+
+        slices[1].rotate_around_own_axis(0.3);
+        assert!(marks(slices.iter().map(|s| s as &dyn Renderable).collect()) > 0);
+    }
+}
@@ -0,0 +1,291 @@
+use std::f32::consts::FRAC_PI_4;
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    cube::{
+        core::{grid::Grid, orientation::Orientation},
+        cube::Cube,
+    },
+    game::render::{Lighting, Renderable, Screen, StickerStyle},
+    utils::color_scheme::Palette,
+};
+
+// the same view of the cube as in the game
+const POSITION: (f32, f32, f32) = (0.0, 0.0, 5.0);
+const ROTATION_Y: f32 = FRAC_PI_4;
+const ROTATION_X: f32 = -FRAC_PI_4;
+const ZP: f32 = 6.0;
+// pixels of the image for each unit of projection, like the game's resolutions
+const SIZE_PER_SCALE: f32 = 5.0;
+
+// an RGBA image, row by row from the top
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * self.width + x) * 4;
+        [self.rgba[i], self.rgba[i + 1], self.rgba[i + 2], self.rgba[i + 3]]
+    }
+
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        let mut png = Vec::new();
+        self.write_png(&mut png)?;
+        Ok(png)
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Could not create '{}': {}", path.display(), e))?;
+        self.write_png(file)
+    }
+
+    // saves as a screenshot taken at the time, under the first name not
+    // taken yet, so that screenshots within the same second are all kept
+    pub fn save_screenshot(&self, dir: &Path, time: SystemTime) -> Result<PathBuf, String> {
+        let mut number = 1;
+        loop {
+            let path = screenshot_path(dir, time, number);
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return self.write_png(file).map(|()| path),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => number += 1,
+                Err(e) => return Err(format!("Could not create '{}': {}", path.display(), e)),
+            }
+        }
+    }
+
+    fn write_png(&self, out: impl std::io::Write) -> Result<(), String> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgba))
+            .map_err(|e| format!("Could not write the image: {}", e))
+    }
+}
+
+// a file in the directory named after the time in UTC, like "kostka-20250131-235959.png";
+// the ones numbered from 2 up get it after the time, like "kostka-20250131-235959-2.png"
+pub fn screenshot_path(dir: &Path, time: SystemTime, number: usize) -> PathBuf {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let time_of_day = seconds % 86400;
+    let suffix = if number > 1 { format!("-{}", number) } else { String::new() };
+    dir.join(format!(
+        "kostka-{:04}{:02}{:02}-{:02}{:02}{:02}{}.png",
+        year, month, day, time_of_day / 3600, time_of_day / 60 % 60, time_of_day % 60, suffix,
+    ))
+}
+
+// the date in UTC of the day counted from 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // counted in 400 year eras starting on the 1st of March
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let march_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * march_month + 2) / 5 + 1) as u32;
+    let month = if march_month < 10 { march_month + 3 } else { march_month - 9 } as u32;
+    (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
+
+// draws a grid into an image without any window; the same settings
+// always give the same image
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    width: usize,
+    height: usize,
+    samples: usize,
+    stickers: StickerStyle,
+    lighting: Option<Lighting>,
+    palette: Palette,
+    orientation: Orientation,
+    rotation_y: f32,
+    rotation_x: f32,
+    center_marks: bool,
+}
+
+impl Snapshot {
+    pub fn new(width: usize, height: usize) -> Snapshot {
+        Snapshot {
+            width,
+            height,
+            samples: 1,
+            stickers: StickerStyle::default(),
+            lighting: None,
+            palette: Palette::default(),
+            orientation: Orientation::identity(),
+            rotation_y: ROTATION_Y,
+            rotation_x: ROTATION_X,
+            center_marks: false,
+        }
+    }
+
+    pub fn supersampled(mut self, samples: usize) -> Snapshot {
+        self.samples = samples;
+        self
+    }
+
+    pub fn with_stickers(mut self, stickers: StickerStyle) -> Snapshot {
+        self.stickers = stickers;
+        self
+    }
+
+    pub fn with_lighting(mut self, lighting: Option<Lighting>) -> Snapshot {
+        self.lighting = lighting;
+        self
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Snapshot {
+        self.palette = palette;
+        self
+    }
+
+    // which faces of the grid are seen on the front and on top
+    pub fn held(mut self, orientation: Orientation) -> Snapshot {
+        self.orientation = orientation;
+        self
+    }
+
+    // the angles the cube is seen from, turned around y first and then x
+    pub fn viewed_from(mut self, rotation_y: f32, rotation_x: f32) -> Snapshot {
+        self.rotation_y = rotation_y;
+        self.rotation_x = rotation_x;
+        self
+    }
+
+    pub fn with_center_marks(mut self, show: bool) -> Snapshot {
+        self.center_marks = show;
+        self
+    }
+
+    pub fn render(&self, grid: &Grid) -> Image {
+        let mut cube = Cube::new(POSITION, self.rotation_y, self.rotation_x);
+        cube.show_center_marks(self.center_marks);
+        cube.hold(self.orientation);
+        cube.apply_grid(grid);
+        self.render_cube(&cube)
+    }
+
+    // the cube as it is, wherever it is turned and placed; only the
+    // settings for drawing it are taken from the snapshot
+    pub fn render_cube(&self, cube: &Cube) -> Image {
+        self.render_renderables(&[cube])
+    }
+
+    // anything placed in the view of the game, like the layers of a cube
+    // in the middle of a move
+    pub fn render_renderables(&self, renderables: &[&dyn Renderable]) -> Image {
+        let scale = self.width.min(self.height) as f32 / SIZE_PER_SCALE;
+        let mut screen = Screen::new(self.width, self.height, ZP, scale)
+            .supersampled(self.samples)
+            .with_stickers(self.stickers)
+            .with_lighting(self.lighting);
+        screen.render(renderables);
+
+        let mut rgba = vec![0; self.width * self.height * 4];
+        screen.draw_into(&mut rgba, &self.palette);
+        Image { width: self.width, height: self.height, rgba }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::grid::GridSide;
+    use crate::cube::{slice::CubeSlice, slice_builder::CubeSliceBuilder};
+    use crate::utils::cube_utils::{Axis, Color};
+
+    fn shows(image: &Image, color: Color) -> bool {
+        let rgba = Palette::default().rgba(color);
+        image.rgba.chunks_exact(4).any(|pixel| pixel == rgba)
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let grid = Grid::new();
+        let snapshot = Snapshot::new(60, 40).supersampled(2);
+        let image = snapshot.render(&grid);
+        assert_eq!(image.rgba.len(), 60 * 40 * 4);
+        assert_eq!(image, snapshot.render(&grid));
+
+        // white on top, green in front and red on the right
+        assert!([Color::White, Color::Green, Color::Red].iter().all(|&c| shows(&image, c)));
+        assert!(!shows(&image, Color::Yellow));
+        assert_eq!(image.pixel(0, 0), Palette::default().rgba(Color::Black));
+
+        let upside_down = Orientation::from_sides(GridSide::Front, GridSide::Bottom).unwrap();
+        let image = snapshot.held(upside_down).render(&grid);
+        assert!(shows(&image, Color::Yellow));
+        assert!(!shows(&image, Color::White));
+
+        // nothing to draw into, but no panic either
+        for (width, height) in [(0, 40), (60, 0), (0, 0)] {
+            let image = Snapshot::new(width, height).render(&grid);
+            assert_eq!((image.width, image.height, image.rgba.len()), (width, height, 0));
+        }
+    }
+
+    #[test]
+    fn test_layers_in_a_move() {
+        let snapshot = Snapshot::new(60, 40);
+        let mut cube = Cube::new(POSITION, ROTATION_Y, ROTATION_X);
+        cube.apply_grid(&Grid::new());
+        let mut slices = CubeSliceBuilder::create_cube_slices(&cube, &Grid::new(), &Axis::Y);
+
+        // the inside shows only while the top layer is turned
+        let layers = |slices: &[CubeSlice; 3]| snapshot.render_renderables(&slices.each_ref().map(|s| s as &dyn Renderable));
+        assert_eq!(layers(&slices), snapshot.render_cube(&cube));
+        slices[0].rotate_around_own_axis(0.5);
+        assert!(shows(&layers(&slices), Color::Gray));
+        assert!(!shows(&snapshot.render_cube(&cube), Color::Gray));
+    }
+
+    #[test]
+    fn test_png() {
+        let image = Snapshot::new(30, 20).render(&Grid::new());
+        let png = image.encode_png().unwrap();
+
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut rgba = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut rgba).unwrap();
+        assert_eq!((info.width, info.height), (30, 20));
+        assert_eq!(rgba, image.rgba);
+    }
+
+    #[test]
+    fn test_screenshot_path() {
+        let time = |seconds| UNIX_EPOCH + std::time::Duration::from_secs(seconds);
+        let path = |seconds| screenshot_path(Path::new("shots"), time(seconds), 1);
+        assert_eq!(path(0), Path::new("shots/kostka-19700101-000000.png"));
+        assert_eq!(path(1_700_000_000), Path::new("shots/kostka-20231114-221320.png"));
+        assert_eq!(path(951_782_400), Path::new("shots/kostka-20000229-000000.png"));
+        assert_eq!(screenshot_path(Path::new("shots"), time(0), 3), Path::new("shots/kostka-19700101-000000-3.png"));
+    }
+
+    #[test]
+    fn test_screenshots_are_kept() {
+        let dir = std::env::temp_dir().join(format!("kostka-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = Snapshot::new(30, 20).render(&Grid::new());
+
+        // taken within the same second, the second one gets its own name
+        let time = UNIX_EPOCH;
+        let first = image.save_screenshot(&dir, time).unwrap();
+        let second = image.save_screenshot(&dir, time).unwrap();
+        assert_eq!(first, screenshot_path(&dir, time, 1));
+        assert_eq!(second, screenshot_path(&dir, time, 2));
+        assert!(first.exists() && second.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+use crate::utils::cube_utils::Color;
+
+const STICKER_COLORS: [Color; 6] = [
+    Color::White,
+    Color::Yellow,
+    Color::Blue,
+    Color::Red,
+    Color::Green,
+    Color::Orange,
+];
+
+// colors of the centers on a solved cube, in the order of the grid faces:
+// top, left, front, right, back, bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub faces: [Color; 6],
+}
+
+impl ColorScheme {
+    pub fn western() -> ColorScheme {
+        ColorScheme {
+            faces: [Color::White, Color::Orange, Color::Green, Color::Red, Color::Blue, Color::Yellow],
+        }
+    }
+
+    pub fn japanese() -> ColorScheme {
+        ColorScheme {
+            faces: [Color::White, Color::Orange, Color::Green, Color::Red, Color::Yellow, Color::Blue],
+        }
+    }
+
+    // either the name of a known scheme or six color letters for
+    // the top, left, front, right, back and bottom face, e.g. "WOGRBY"
+    pub fn parse(source: &str) -> Result<ColorScheme, String> {
+        match source.trim().to_lowercase().as_str() {
+            "western" => return Ok(ColorScheme::western()),
+            "japanese" => return Ok(ColorScheme::japanese()),
+            _ => {}
+        }
+
+        let colors = source.trim().chars()
+            .map(|c| Color::from_char(c).ok_or_else(|| format!("Unknown color '{}' in scheme {}", c, source)))
+            .collect::<Result<Vec<Color>, String>>()?;
+
+        let faces: [Color; 6] = colors.try_into()
+            .map_err(|_| format!("Scheme {} must have exactly 6 colors", source))?;
+
+        for (i, color) in faces.iter().enumerate() {
+            if !STICKER_COLORS.contains(color) {
+                return Err(format!("{:?} can't be used as a face color", color));
+            }
+            if faces[..i].contains(color) {
+                return Err(format!("{:?} is used for more than one face", color));
+            }
+        }
+
+        Ok(ColorScheme { faces })
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> ColorScheme {
+        ColorScheme::western()
+    }
+}
+
+// rgba values used when drawing every color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    colors: [[u8; 4]; Color::COUNT],
+}
+
+impl Palette {
+    // friendlier to red-green color blindness, based on the Okabe-Ito colors
+    pub fn colorblind() -> Palette {
+        let mut palette = Palette::default();
+        palette.set(Color::Yellow, [240, 228, 66, 0xff]);
+        palette.set(Color::Blue, [0, 114, 178, 0xff]);
+        palette.set(Color::Red, [204, 121, 167, 0xff]);
+        palette.set(Color::Green, [0, 158, 115, 0xff]);
+        palette.set(Color::Orange, [230, 159, 0, 0xff]);
+        palette
+    }
+
+    pub fn from_name(name: &str) -> Option<Palette> {
+        match name.to_lowercase().as_str() {
+            "default" => Some(Palette::default()),
+            "colorblind" => Some(Palette::colorblind()),
+            _ => None,
+        }
+    }
+
+    pub fn rgba(&self, color: Color) -> [u8; 4] {
+        self.colors[color as usize]
+    }
+
+    pub fn set(&mut self, color: Color, rgba: [u8; 4]) {
+        self.colors[color as usize] = rgba;
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        let mut palette = Palette { colors: [[0; 4]; Color::COUNT] };
+        for color in Color::ALL {
+            palette.set(color, color.rgba());
+        }
+        palette
+    }
+}
+
+// hex color, "#rrggbb" or "#rrggbbaa" with an optional '#'
+fn parse_rgba(source: &str) -> Result<[u8; 4], String> {
+    let hex = source.strip_prefix('#').unwrap_or(source);
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid color value {}", source));
+    }
+
+    let mut rgba = [0xff; 4];
+    for (i, value) in rgba.iter_mut().enumerate().take(hex.len() / 2) {
+        *value = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    Ok(rgba)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ColorSettings {
+    pub scheme: ColorScheme,
+    pub palette: Palette,
+}
+
+impl ColorSettings {
+    // reads lines like `scheme: japanese` or `red: #d55e00` on top of the
+    // current settings; lines starting with '#' are comments
+    pub fn load_str(&mut self, source: &str) -> Result<(), String> {
+        let mut settings = self.clone();
+
+        for (line_no, line) in source.lines().enumerate() {
+            let line = line.trim();
+            let err = |e: String| format!("line {}: {}", line_no + 1, e);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once(':')
+                .ok_or_else(|| err(format!("expected `<name>: <value>`, got {}", line)))?;
+            let (key, value) = (key.trim().to_lowercase(), value.trim());
+
+            match key.as_str() {
+                "scheme" => settings.scheme = ColorScheme::parse(value).map_err(err)?,
+                "palette" => {
+                    settings.palette = Palette::from_name(value)
+                        .ok_or_else(|| err(format!("Unknown palette {}", value)))?;
+                }
+                _ => {
+                    let color = Color::from_name(&key)
+                        .ok_or_else(|| err(format!("Unknown color {}", key)))?;
+                    settings.palette.set(color, parse_rgba(value).map_err(err)?);
+                }
+            }
+        }
+
+        *self = settings;
+        Ok(())
+    }
+
+    pub fn load_file(&mut self, path: &str) -> Result<(), String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+        self.load_str(&source).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::core::{algorithm::parse_algorithm, grid::Grid};
+
+    #[test]
+    fn test_parse_scheme() {
+        assert_eq!(ColorScheme::parse("Western").unwrap(), ColorScheme::western());
+        assert_eq!(ColorScheme::parse("WOGRBY").unwrap(), ColorScheme::western());
+        assert_eq!(ColorScheme::parse("wogryb").unwrap(), ColorScheme::japanese());
+
+        assert!(ColorScheme::parse("WOGRB").is_err());
+        assert!(ColorScheme::parse("WOGRBW").is_err());
+        assert!(ColorScheme::parse("WOGRBX").is_err());
+    }
+
+    #[test]
+    fn test_grid_with_scheme() {
+        let mut grid = Grid::with_scheme(&ColorScheme::japanese());
+        assert!(grid.is_solved());
+        assert_eq!(grid.faces[4].grid[1][1], Color::Yellow);
+
+        let moves = parse_algorithm("R U R' U'").unwrap();
+        grid.apply_moves(&moves);
+        assert!(!grid.is_solved());
+        grid.apply_moves(&crate::cube::core::algorithm::invert_algorithm(&moves));
+        assert!(grid.is_solved());
+    }
+
+    #[test]
+    fn test_palette_has_every_color() {
+        for (idx, &color) in Color::ALL.iter().enumerate() {
+            assert_eq!(color as usize, idx);
+            assert_eq!(Palette::default().rgba(color), color.rgba());
+        }
+    }
+
+    #[test]
+    fn test_load_settings() {
+        let mut settings = ColorSettings::default();
+        settings.load_str("# comment\nscheme: japanese\npalette: colorblind\nred: #102030\n").unwrap();
+
+        assert_eq!(settings.scheme, ColorScheme::japanese());
+        assert_eq!(settings.palette.rgba(Color::Red), [0x10, 0x20, 0x30, 0xff]);
+        assert_eq!(settings.palette.rgba(Color::Green), Palette::colorblind().rgba(Color::Green));
+
+        let before = settings.clone();
+        assert!(settings.load_str("green: 00ff00\npurple: 00ff00").is_err());
+        assert_eq!(settings.palette, before.palette);
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Color {
     White,
     Yellow,
@@ -8,9 +8,53 @@ pub enum Color {
     Orange,
     Gray,
     Black,
+    // the body of the cube between and behind the stickers
+    Plastic,
 }
 
 impl Color {
+    // every color, in the order they are declared; the palette has a place
+    // for each of them, so a new color has to be added here too
+    pub const ALL: [Color; 9] = [
+        Color::White,
+        Color::Yellow,
+        Color::Blue,
+        Color::Red,
+        Color::Green,
+        Color::Orange,
+        Color::Gray,
+        Color::Black,
+        Color::Plastic,
+    ];
+    pub const COUNT: usize = Color::ALL.len();
+
+    pub fn from_char(c: char) -> Option<Color> {
+        match c.to_ascii_uppercase() {
+            'W' => Some(Color::White),
+            'Y' => Some(Color::Yellow),
+            'B' => Some(Color::Blue),
+            'R' => Some(Color::Red),
+            'G' => Some(Color::Green),
+            'O' => Some(Color::Orange),
+            _ => None,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Color> {
+        match name.to_lowercase().as_str() {
+            "white" => Some(Color::White),
+            "yellow" => Some(Color::Yellow),
+            "blue" => Some(Color::Blue),
+            "red" => Some(Color::Red),
+            "green" => Some(Color::Green),
+            "orange" => Some(Color::Orange),
+            "gray" | "grey" => Some(Color::Gray),
+            "black" => Some(Color::Black),
+            "plastic" => Some(Color::Plastic),
+            _ => None,
+        }
+    }
+
     pub fn to_ansi(&self) -> &str {
         match self {
             Color::White => "\x1b[97m",
@@ -20,6 +64,7 @@ impl Color {
             Color::Green => "\x1b[92m",
             Color::Orange => "\x1b[38;5;208m",
             Color::Gray  => "\x1b[90m",
+            Color::Plastic => "\x1b[30m",
             Color::Black => panic!()
         }
     }
@@ -34,6 +79,7 @@ impl Color {
             Color::Orange  => [254, 146, 43, 0xff],
             Color::Gray    => [160, 152, 160, 0xff],
             Color::Black   => [35, 32, 47, 0xff],
+            Color::Plastic => [12, 12, 14, 0xff],
         }
     }
 }
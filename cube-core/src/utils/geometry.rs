@@ -4,7 +4,7 @@ pub struct Point2D {
     pub y: isize,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point3D {
     pub x: f32,
     pub y: f32,
@@ -109,6 +109,128 @@ impl Point3D {
     }
 }
 
+// rotation of points around the origin, stored by rows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3 {
+    pub rows: [Point3D; 3],
+}
+
+impl Matrix3 {
+    pub fn identity() -> Matrix3 {
+        Matrix3 {
+            rows: [
+                Point3D { x: 1.0, y: 0.0, z: 0.0 },
+                Point3D { x: 0.0, y: 1.0, z: 0.0 },
+                Point3D { x: 0.0, y: 0.0, z: 1.0 },
+            ],
+        }
+    }
+
+    // the same turn as `Point3D::rotate_x`
+    pub fn rotation_x(angle: f32) -> Matrix3 {
+        let (sin, cos) = (angle.sin(), angle.cos());
+        Matrix3 {
+            rows: [
+                Point3D { x: 1.0, y: 0.0, z: 0.0 },
+                Point3D { x: 0.0, y: cos, z: -sin },
+                Point3D { x: 0.0, y: sin, z: cos },
+            ],
+        }
+    }
+
+    // the same turn as `Point3D::rotate_y`
+    pub fn rotation_y(angle: f32) -> Matrix3 {
+        let (sin, cos) = (angle.sin(), angle.cos());
+        Matrix3 {
+            rows: [
+                Point3D { x: cos, y: 0.0, z: sin },
+                Point3D { x: 0.0, y: 1.0, z: 0.0 },
+                Point3D { x: -sin, y: 0.0, z: cos },
+            ],
+        }
+    }
+
+    // the same turn as `Point3D::rotate_z`
+    pub fn rotation_z(angle: f32) -> Matrix3 {
+        let (sin, cos) = (angle.sin(), angle.cos());
+        Matrix3 {
+            rows: [
+                Point3D { x: cos, y: -sin, z: 0.0 },
+                Point3D { x: sin, y: cos, z: 0.0 },
+                Point3D { x: 0.0, y: 0.0, z: 1.0 },
+            ],
+        }
+    }
+
+    // the same turn as `Point3D::rotate_around_axis` around the origin
+    pub fn rotation_around(axis: Point3D, angle: f32) -> Matrix3 {
+        let Point3D { x, y, z } = axis.normalize();
+        let (sin, cos) = (angle.sin(), angle.cos());
+        let t = 1.0 - cos;
+        Matrix3 {
+            rows: [
+                Point3D { x: cos + x * x * t, y: z * sin + x * y * t, z: -y * sin + x * z * t },
+                Point3D { x: -z * sin + x * y * t, y: cos + y * y * t, z: x * sin + y * z * t },
+                Point3D { x: y * sin + x * z * t, y: -x * sin + y * z * t, z: cos + z * z * t },
+            ],
+        }
+    }
+
+    pub fn apply(&self, p: Point3D) -> Point3D {
+        Point3D {
+            x: self.rows[0].dot(&p),
+            y: self.rows[1].dot(&p),
+            z: self.rows[2].dot(&p),
+        }
+    }
+
+    // rotation doing `other` first and then this one
+    pub fn multiply(&self, other: &Matrix3) -> Matrix3 {
+        let columns = other.transpose();
+        Matrix3 {
+            rows: self.rows.map(|row| Point3D {
+                x: row.dot(&columns.rows[0]),
+                y: row.dot(&columns.rows[1]),
+                z: row.dot(&columns.rows[2]),
+            }),
+        }
+    }
+
+    // the nearest proper rotation, taking away the error piled up by
+    // multiplying many rotations together
+    pub fn orthonormalize(&self) -> Matrix3 {
+        let [a, b, _] = self.rows;
+        let a = a.normalize();
+        let b = b.subtract(&a.scalar_multiply(a.dot(&b))).normalize();
+        Matrix3 { rows: [a, b, a.cross(&b)] }
+    }
+
+    // for rotations this is also the inverse
+    pub fn transpose(&self) -> Matrix3 {
+        let [a, b, c] = self.rows;
+        Matrix3 {
+            rows: [
+                Point3D { x: a.x, y: b.x, z: c.x },
+                Point3D { x: a.y, y: b.y, z: c.y },
+                Point3D { x: a.z, y: b.z, z: c.z },
+            ],
+        }
+    }
+}
+
+// places model geometry: turned around the origin, then moved to the position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub rotation: Matrix3,
+    pub position: Point3D,
+}
+
+impl Transform {
+    pub fn apply(&self, p: Point3D) -> Point3D {
+        self.rotation.apply(p).translate(self.position)
+    }
+}
+
 pub struct Triangle (
     pub Point2D,
     pub Point2D,
@@ -1,2 +1,3 @@
 pub mod cube_utils;
+pub mod color_scheme;
 pub mod geometry;
\ No newline at end of file
@@ -1,6 +1,15 @@
 use std::f32::consts::PI;
+use std::time::Duration;
 
-use clap::{Parser, ValueEnum};
+use clap::{CommandFactory, Parser, ValueEnum, error::ErrorKind};
+use cube_core::{
+    cube::core::{mask::MaskPreset, predicate::Goal},
+    game::render::{Lighting, StickerStyle},
+    utils::color_scheme::{ColorScheme, ColorSettings, Palette},
+};
+
+// how round the corners of rounded stickers are
+const ROUNDED_CORNERS: f32 = 0.5;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Kostka Cube", long_about = None)]
@@ -9,6 +18,10 @@ struct Cli {
     #[arg(long, value_enum, default_value_t = Resolution::Medium)]
     res: Resolution,
 
+    /// Set anti-aliasing, smoothing the edges of the stickers (off, low, high)
+    #[arg(long, value_enum, default_value_t = AntiAliasing::Off)]
+    aa: AntiAliasing,
+
     /// Set rotation speed (low, medium, high)
     #[arg(long, value_enum, default_value_t = RotationSpeed::Medium)]
     rs: RotationSpeed,
@@ -16,42 +29,135 @@ struct Cli {
     /// Set move speed (low, medium, high)
     #[arg(long, value_enum, default_value_t = MoveSpeed::Medium)]
     ms: MoveSpeed,
+
+    /// Set how long a move takes in milliseconds, overrides the move speed
+    #[arg(long, value_name = "MS")]
+    move_duration: Option<u64>,
+
+    /// Set how moves speed up and slow down (linear, ease-out, snap); snap ends a move at once, but still waits out --move-duration if given
+    #[arg(long, value_enum, default_value_t = Easing::EaseOut)]
+    easing: Easing,
+
+    /// Set color scheme (western, japanese or six colors for U L F R B D faces, e.g. WOGRBY)
+    #[arg(long, value_parser = ColorScheme::parse)]
+    scheme: Option<ColorScheme>,
+
+    /// Set sticker palette (default, colorblind)
+    #[arg(long, value_enum)]
+    palette: Option<PaletteName>,
+
+    /// Load color scheme and palette from a file
+    #[arg(long, value_name = "FILE")]
+    colors: Option<String>,
+
+    /// Set how much of the plastic shows between the stickers, as a part of a sticker (0 to 0.5)
+    #[arg(long, default_value_t = 0.1, value_parser = parse_gap)]
+    gap: f32,
+
+    /// Round the corners of the stickers
+    #[arg(long)]
+    rounded: bool,
+
+    /// Set how much the faces are shaded by a light from above, to tell them apart (off, soft, strong)
+    #[arg(long, value_enum, default_value_t = Light::Soft)]
+    light: Light,
+
+    /// Gray out part of the cube for recognition training (last-layer, cross-and-pair, oll)
+    #[arg(long, value_enum)]
+    mask: Option<Mask>,
+
+    /// Show how the centers are turned and require them oriented to finish a solve
+    #[arg(long)]
+    supercube: bool,
+
+    /// Save screenshots this many times bigger than the window, drawing the cube again without the timer (1 to 8)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=8))]
+    screenshot_scale: u32,
+
+    /// Stop the timer on a custom goal, e.g. "cross:white", "f2l:yellow & face:U" or "auf(solved)"
+    #[arg(long, value_parser = Goal::parse)]
+    goal: Option<Goal>,
 }
 
 pub struct GameArgs {
     pub width: u32,
     pub height: u32,
+    pub samples: usize,
     pub rotation_angle: f32,
-    pub no_steps: u8,
+    pub move_duration: Duration,
+    pub easing: Easing,
     pub projection_scale: f32,
+    pub colors: ColorSettings,
+    pub stickers: StickerStyle,
+    pub lighting: Option<Lighting>,
+    pub mask: Option<MaskPreset>,
+    pub supercube: bool,
+    pub goal: Goal,
+    pub screenshot_scale: u32,
 }
 
 impl GameArgs {
-    fn new(
-        resolution: Resolution,
-        rotation_speed: RotationSpeed,
-        move_speed: MoveSpeed,
-    ) -> GameArgs {
-        let dimension = resolution.get_dimension();
+    fn new(cli: Cli, colors: ColorSettings) -> GameArgs {
+        let dimension = cli.res.get_dimension();
+
+        // snapping moves don't wait for anything, unless told how long to take
+        let move_duration = match (cli.move_duration, cli.easing) {
+            (Some(ms), _) => Duration::from_millis(ms),
+            (None, Easing::Snap) => Duration::ZERO,
+            (None, _) => cli.ms.get_duration(),
+        };
 
         GameArgs {
             width: dimension,
             height: dimension,
-            rotation_angle: rotation_speed.get_rotation_angle(),
-            no_steps: move_speed.get_no_steps(),
-            projection_scale: resolution.get_projection_scale(),
+            samples: cli.aa.get_samples(),
+            rotation_angle: cli.rs.get_rotation_angle(),
+            move_duration,
+            easing: cli.easing,
+            projection_scale: cli.res.get_projection_scale(),
+            colors,
+            stickers: StickerStyle {
+                gap: cli.gap,
+                rounding: if cli.rounded { ROUNDED_CORNERS } else { 0.0 },
+            },
+            lighting: cli.light.get_lighting(),
+            mask: cli.mask.map(Mask::get_preset),
+            supercube: cli.supercube,
+            goal: cli.goal.unwrap_or_else(|| if cli.supercube {
+                Goal::Solved.and(Goal::CentersOriented)
+            } else {
+                Goal::Solved
+            }),
+            screenshot_scale: cli.screenshot_scale,
         }
     }
 
     pub fn parse() -> GameArgs {
         let cli = Cli::parse();
 
-        GameArgs::new(
-            cli.res,
-            cli.rs,
-            cli.ms,
-        )
+        // the file is read first, so the flags can override it
+        let mut colors = ColorSettings::default();
+        if let Some(path) = &cli.colors
+            && let Err(e) = colors.load_file(path) {
+            Cli::command().error(ErrorKind::ValueValidation, e).exit();
+        }
+        if let Some(scheme) = cli.scheme {
+            colors.scheme = scheme;
+        }
+        if let Some(palette) = cli.palette {
+            colors.palette = palette.get_palette();
+        }
+
+        GameArgs::new(cli, colors)
+    }
+}
+
+fn parse_gap(source: &str) -> Result<f32, String> {
+    let gap: f32 = source.parse().map_err(|_| format!("Incorrect gap '{}'", source))?;
+    if !(0.0..=0.5).contains(&gap) {
+        return Err(format!("Gap '{}' is not between 0 and 0.5", source));
     }
+    Ok(gap)
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -83,6 +189,46 @@ impl Resolution {
     }
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum AntiAliasing {
+    Off,
+    Low,
+    High,
+}
+
+impl AntiAliasing {
+    // samples taken along each side of a pixel
+    fn get_samples(self) -> usize {
+        use self::AntiAliasing::*;
+
+        match self {
+            Off => 1,
+            Low => 2,
+            High => 4,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum Light {
+    Off,
+    Soft,
+    Strong,
+}
+
+impl Light {
+    fn get_lighting(self) -> Option<Lighting> {
+        use self::Light::*;
+
+        let ambient = match self {
+            Off => return None,
+            Soft => 0.7,
+            Strong => 0.4,
+        };
+        Some(Lighting { ambient, ..Lighting::default() })
+    }
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum RotationSpeed {
     Low,
@@ -110,13 +256,69 @@ pub enum MoveSpeed {
 }
 
 impl MoveSpeed {
-    fn get_no_steps(self) -> u8 {
+    fn get_duration(self) -> Duration {
         use self::MoveSpeed::*;
 
         match self {
-            Low => 64,
-            Medium => 32,
-            High => 16,
+            Low => Duration::from_millis(533),
+            Medium => Duration::from_millis(267),
+            High => Duration::from_millis(133),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum Easing {
+    Linear,
+    EaseOut,
+    Snap,
+}
+
+impl Easing {
+    // how far along the move is after the given part of its duration
+    pub fn apply(self, progress: f32) -> f32 {
+        use self::Easing::*;
+
+        match self {
+            Linear => progress,
+            EaseOut => 1.0 - (1.0 - progress).powi(3),
+            Snap => 1.0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum PaletteName {
+    Default,
+    Colorblind,
+}
+
+impl PaletteName {
+    fn get_palette(self) -> Palette {
+        use self::PaletteName::*;
+
+        match self {
+            Default => Palette::default(),
+            Colorblind => Palette::colorblind(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum Mask {
+    LastLayer,
+    CrossAndPair,
+    Oll,
+}
+
+impl Mask {
+    fn get_preset(self) -> MaskPreset {
+        use self::Mask::*;
+
+        match self {
+            LastLayer => MaskPreset::LastLayer,
+            CrossAndPair => MaskPreset::CrossAndPair,
+            Oll => MaskPreset::Oll,
         }
     }
 }
\ No newline at end of file
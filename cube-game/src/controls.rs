@@ -1,27 +1,45 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::{HashMap, VecDeque}, rc::Rc};
 
-use cube_core::cube::slice::CubeMove;
+use cube_core::cube::{core::grid::{GridSide, MoveDirection}, slice::CubeMove};
+use winit::keyboard::KeyCode;
 
-use crate::{game::Game, render::AnimatedMoveInfo, timer::start_timer};
+use crate::{game::Game, render::{AnimatedCubeTurn, AnimatedMoveInfo}, timer::start_timer};
+
+#[derive(Debug, Clone)]
+pub enum MoveInput {
+    Turn(CubeMove),
+    Undo,
+    Redo,
+    // turns the whole cube like the side seen in the starting view
+    TurnCube(GridSide, MoveDirection),
+    Snap,
+}
+
+// moves typed beyond this while animating are dropped
+pub const MAX_QUEUED_MOVES: usize = 16;
 
 pub struct Controls {
     pub animated_move: Option<Rc<RefCell<AnimatedMoveInfo>>>,
-    pub next_move: Option<CubeMove>,
+    pub animated_turn: Option<AnimatedCubeTurn>,
+    pub move_queue: VecDeque<MoveInput>,
     pub double_move: bool,
     pub rotation_x: f32,
     pub rotation_y: f32,
     pub rotation_z: f32,
+    pub zoom: f32,
 }
 
 impl Controls {
     pub fn new() -> Controls {
         Controls {
             animated_move: None,
-            next_move: None,
+            animated_turn: None,
+            move_queue: VecDeque::with_capacity(MAX_QUEUED_MOVES),
             double_move: false,
             rotation_x: 0.0,
             rotation_y: 0.0,
             rotation_z: 0.0,
+            zoom: 0.0,
         }
     }
 }
@@ -50,6 +68,19 @@ fn update_controls_rotations(game: &mut Game) {
     } else {
         game.controls.rotation_z = 0.0;
     }
+    if game.input.key_held(ZOOM_IN_CODE) {
+        game.controls.zoom = 1.0;
+    } else if game.input.key_held(ZOOM_OUT_CODE) {
+        game.controls.zoom = -1.0;
+    } else {
+        game.controls.zoom = 0.0;
+    }
+}
+
+fn queue_move(game: &mut Game, input: MoveInput) {
+    if game.controls.move_queue.len() < MAX_QUEUED_MOVES {
+        game.controls.move_queue.push_back(input);
+    }
 }
 
 pub fn update_controls(game: &mut Game) {
@@ -59,19 +90,30 @@ pub fn update_controls(game: &mut Game) {
 
     game.controls.double_move = game.input.key_held(DOUBLE_MOVE);
 
-    let next_move = move_bindings()
-        .into_iter()
-        .find(|(key_code, _)| game.input.key_pressed(*key_code))
-        .map(|(_, (side, direction))| {
-            CubeMove::from_side(side, direction)
-        }
-    );
+    // double moves are decided when typed, not when their turn comes
+    let double_move = game.controls.double_move;
+    let pressed = |bindings: HashMap<KeyCode, (GridSide, MoveDirection)>| {
+        bindings.into_iter()
+            .find(|(key_code, _)| game.input.key_pressed(*key_code))
+            .map(|(_, (side, direction))| (side, if double_move { MoveDirection::Double } else { direction }))
+    };
+
+    let next_move = pressed(move_bindings()).map(|(side, direction)| CubeMove::from_side(side, direction));
+    let next_turn = pressed(turn_bindings());
 
-    if next_move.is_some() {
-        game.controls.next_move = next_move;
+    if let Some(mv) = next_move {
+        queue_move(game, MoveInput::Turn(mv));
         if !game.start {
             game.start = true;
             start_timer(game);
         }
+    } else if game.input.key_pressed(UNDO_CODE) {
+        queue_move(game, MoveInput::Undo);
+    } else if game.input.key_pressed(REDO_CODE) {
+        queue_move(game, MoveInput::Redo);
+    } else if let Some((side, direction)) = next_turn {
+        queue_move(game, MoveInput::TurnCube(side, direction));
+    } else if game.input.key_pressed(SNAP_CODE) {
+        queue_move(game, MoveInput::Snap);
     }
 }
\ No newline at end of file
@@ -4,18 +4,7 @@ use crate::game::Game;
 const X_PADDING: i32 = 10;
 
 pub fn draw(game: &mut Game) {
-    for (i, pixel) in game.pixels.frame_mut().chunks_exact_mut(4).enumerate() {
-        let x = (i % game.args.width as usize) as u32;
-        let y = (i / game.args.width as usize) as u32;
-
-        let rgba = if let Some(color) = game.screen.color_at(x as i16, y as i16) {
-            color.rgba()
-        } else {
-            Color::Black.rgba()
-        };
-
-        pixel.copy_from_slice(&rgba);
-    }
+    game.screen.draw_into(game.pixels.frame_mut(), &game.args.colors.palette);
 
     draw_time(game);
 }
@@ -62,7 +51,7 @@ fn draw_text(
         .layout(text, scale, point(x as f32, y as f32 + v_metrics.ascent))
         .collect();
 
-    let background_rgba = Color::Black.rgba();
+    let background_rgba = game.args.colors.palette.rgba(Color::Black);
     for glyph in glyphs {
         if let Some(bb) = glyph.pixel_bounding_box() {
             glyph.draw(|gx, gy, v| {
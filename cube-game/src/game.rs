@@ -16,9 +16,10 @@ use game_loop::{game_loop, Time, TimeTrait as _};
 use cube_core::{
     cube::{
         core::{
-            grid::{Grid, MoveDirection},
+            grid::{Grid, GridSide, MoveDirection},
+            history::MoveHistory,
             scramble::scramble
-        }, cube::Cube, slice::CubeMove, slice_builder::CubeSliceBuilder
+        }, cube::{Cube, CUBE_SIZE}, slice::CubeMove, slice_builder::CubeSliceBuilder
     },
     game::render::Screen,
 };
@@ -27,9 +28,9 @@ use core::f32;
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::{args::GameArgs, controls::{update_controls, Controls}, draw::draw, key_mapping::{SCRAMBLE_CODE, TIMER_CODE}, render::{animate_rotation, AnimatedMoveInfo}, timer::*};
+use crate::{args::GameArgs, controls::{update_controls, Controls, MoveInput}, draw::draw, key_mapping::{SCRAMBLE_CODE, SCREENSHOT_CODE, TIMER_CODE}, render::{animate_cube_turn, animate_rotation, AnimatedCubeTurn, AnimatedMoveInfo}, screenshot::save_screenshot, timer::*};
 
 const X_ROT_INIT: f32 = -f32::consts::FRAC_PI_4;
 const Y_ROT_INIT: f32 = f32::consts::FRAC_PI_4;
@@ -37,6 +38,13 @@ const X_INIT: f32 = 0.0;
 const Y_INIT: f32 = 0.0;
 const Z_INIT: f32 = 5.0;
 const ZP: f32 = 6.0;
+// how close and how far the cube can be moved, and how fast
+const Z_MIN: f32 = 2.0;
+const Z_MAX: f32 = 20.0;
+const ZOOM_STEP: f32 = 0.03;
+// the eye must stay outside of the sphere around the corners of the cube,
+// with some room to spare; from inside every face turns away from it
+const _: () = assert!(Z_MIN * Z_MIN > 3.0 * (CUBE_SIZE / 2.0) * (CUBE_SIZE / 2.0) * 1.2);
 
 const WINDOW_SIZE: u32 = 320;
 const FPS: u32 = 120;
@@ -106,6 +114,11 @@ pub fn game(args: GameArgs) -> Result<(), Error> {
                     toggle_timer(&mut g.game);
                 }
 
+                // Save what is on the screen
+                if g.game.input.key_pressed(SCREENSHOT_CODE) {
+                    save_screenshot(&g.game);
+                }
+
                 // Update controls
                 update_controls(&mut g.game);
 
@@ -134,7 +147,7 @@ pub struct Game {
     pub start: bool,
     pub screen: Screen,
     pub cube: Cube,
-    pub grid: Grid,
+    pub history: MoveHistory,
     pub controls: Controls,
     pub input: WinitInputHelper,
     pub pixels: Pixels<'static>,
@@ -149,14 +162,20 @@ impl Game {
             args.height as usize, 
             ZP, 
             args.projection_scale,
-        );
+        )
+        .supersampled(args.samples)
+        .with_stickers(args.stickers)
+        .with_lighting(args.lighting);
 
         let position: (f32, f32, f32) = (X_INIT, Y_INIT, Z_INIT);
         let angle_x = X_ROT_INIT;
         let angle_y = Y_ROT_INIT;
 
         let mut cube = Cube::new(position, angle_y, angle_x);
-        let grid = Grid::new();
+        cube.show_center_marks(args.supercube);
+        // only drawn gray, the grid keeps its colors for the goals
+        cube.set_mask(args.mask);
+        let grid = Grid::with_scheme(&args.colors.scheme);
         cube.apply_grid(&grid);
 
         let controls = Controls::new();
@@ -170,7 +189,7 @@ impl Game {
             start: false,
             screen,
             cube,
-            grid,
+            history: MoveHistory::new(grid),
             controls, 
             input,
             pixels,
@@ -179,13 +198,17 @@ impl Game {
         }
     }
 
-    fn handle_next_move(&mut self, mv: CubeMove) {
-        let mut translated_move = self.cube.translate_move(mv);
-        if self.controls.double_move {
-            translated_move.direction = MoveDirection::Double;
+    fn handle_next_move(&mut self, input: MoveInput) {
+        match input {
+            MoveInput::Turn(mv) => {
+                let translated_move = self.cube.translate_move(mv);
+                self.make_move(translated_move);
+            }
+            MoveInput::Undo => self.undo_move(),
+            MoveInput::Redo => self.redo_move(),
+            MoveInput::TurnCube(side, direction) => self.turn_cube(side, direction),
+            MoveInput::Snap => self.cube.snap(),
         }
-        self.make_move(translated_move);
-        self.controls.next_move = None;
     }
 
     fn handle_animation_step(&mut self, am_rc: Rc<RefCell<AnimatedMoveInfo>>) {
@@ -200,6 +223,7 @@ impl Game {
             if self.controls.rotation_z != 0.0 {
                 slice.rotate(Axis::Z, self.controls.rotation_z * self.args.rotation_angle);
             }
+            slice.cube_transform.position = self.cube.position;
         }
 
         if animate_rotation(self, &mut am) {
@@ -219,44 +243,94 @@ impl Game {
         }
     }
 
+    fn update_cube_zoom(&mut self) {
+        let z = self.cube.position.z - self.controls.zoom * ZOOM_STEP;
+        self.cube.position.z = z.clamp(Z_MIN, Z_MAX);
+    }
+
     fn update(&mut self) {
-        if self.grid.is_solved() {
+        if self.args.goal.is_met(self.history.grid()) {
             stop_timer(self);
         }
 
+        if self.controls.zoom != 0.0 {
+            self.update_cube_zoom();
+        }
+
         if let Some(am_rc) = self.controls.animated_move.take() {
             self.handle_animation_step(am_rc);
-        } else {
-            if let Some(mv) = self.controls.next_move.clone() {
-                self.handle_next_move(mv);
-            }
+        } else if self.controls.animated_turn.is_some() {
+            animate_cube_turn(self);
+        } else if let Some(input) = self.controls.move_queue.pop_front() {
+            self.handle_next_move(input);
         }
         
         self.update_cube_rotation();
 
-        self.cube.update_side_map();
         if self.controls.animated_move.is_none() {
             self.screen.clear_screen();
-            self.screen.render(vec![&self.cube]);
+            self.screen.render(&[&self.cube]);
         }
     }
 
     fn reset_game(&mut self) {
         self.start = false;
+        self.controls.move_queue.clear();
         reset_timer(self);
-        scramble(&mut self.grid);
-        self.cube.apply_grid(&self.grid);
+        let mut grid = self.history.grid().clone();
+        scramble(&mut grid);
+        self.cube.apply_grid(&grid);
+        self.history.reset(grid);
     }
 
-    fn make_move(&mut self, cube_move: CubeMove) {
-        let slices = CubeSliceBuilder::create_cube_slices(&self.cube, &self.grid, &cube_move.axis);
+    // the more moves are waiting, the faster this one goes
+    fn next_move_duration(&self) -> Duration {
+        self.args.move_duration / (1 + self.controls.move_queue.len() as u32)
+    }
+
+    fn animate_move(&mut self, grid: &Grid, cube_move: &CubeMove) {
+        let slices = CubeSliceBuilder::create_cube_slices(&self.cube, grid, &cube_move.axis);
+        let duration = self.next_move_duration();
         self.controls.animated_move = Some(Rc::new(RefCell::new(AnimatedMoveInfo {
             slices,
             slice_id: cube_move.order.idx(),
-            current_step: 0,
+            start: Instant::now(),
+            duration,
+            angle_done: 0.0,
             cube_move: cube_move.clone(),
         })));
-        self.grid.apply_move(cube_move);
-        self.cube.apply_grid(&self.grid);
+        self.cube.apply_grid(self.history.grid());
+    }
+
+    // the cube only turns in view, the grid and history stay as they are
+    fn turn_cube(&mut self, side: GridSide, direction: MoveDirection) {
+        let axis = self.cube.face_normal(self.cube.facing(side));
+        let target = self.cube.orientation().turn(side, direction);
+        let duration = self.next_move_duration();
+        self.controls.animated_turn = Some(AnimatedCubeTurn::new(axis, direction, target, duration));
+    }
+
+    fn make_move(&mut self, cube_move: CubeMove) {
+        let before = self.history.grid().clone();
+        record_event(self, SolveEventKind::Move(cube_move.clone()));
+        self.history.apply_move(cube_move.clone());
+        self.animate_move(&before, &cube_move);
+    }
+
+    // undoing animates the inverse move
+    fn undo_move(&mut self) {
+        let before = self.history.grid().clone();
+        if let Some(inverse) = self.history.undo() {
+            record_event(self, SolveEventKind::Undo(inverse.clone()));
+            self.animate_move(&before, &inverse);
+        }
+    }
+
+    fn redo_move(&mut self) {
+        let before = self.history.grid().clone();
+        if let Some(cube_move) = self.history.redo() {
+            record_event(self, SolveEventKind::Redo(cube_move.clone()));
+            self.animate_move(&before, &cube_move);
+        }
     }
 }
\ No newline at end of file
@@ -7,6 +7,10 @@ use winit::keyboard::KeyCode;
 
 pub const SCRAMBLE_CODE: KeyCode = KeyCode::Digit1;
 pub const TIMER_CODE: KeyCode = KeyCode::Digit2;
+pub const SCREENSHOT_CODE: KeyCode = KeyCode::Digit3;
+
+pub const UNDO_CODE: KeyCode = KeyCode::Backspace;
+pub const REDO_CODE: KeyCode = KeyCode::Enter;
 
 // rotation key mapping
 
@@ -19,6 +23,22 @@ pub const ROTATE_Y_PRIM_CODE: KeyCode = KeyCode::KeyM;
 pub const ROTATE_Z_CODE: KeyCode = KeyCode::KeyV;
 pub const ROTATE_Z_PRIM_CODE: KeyCode = KeyCode::KeyN;
 
+pub const SNAP_CODE: KeyCode = KeyCode::KeyB;
+
+pub const ZOOM_IN_CODE: KeyCode = KeyCode::Equal;
+pub const ZOOM_OUT_CODE: KeyCode = KeyCode::Minus;
+
+// whole cube turn key mapping
+
+pub const TURN_X_CODE: KeyCode = KeyCode::ArrowUp;
+pub const TURN_X_PRIM_CODE: KeyCode = KeyCode::ArrowDown;
+
+pub const TURN_Y_CODE: KeyCode = KeyCode::ArrowLeft;
+pub const TURN_Y_PRIM_CODE: KeyCode = KeyCode::ArrowRight;
+
+pub const TURN_Z_CODE: KeyCode = KeyCode::Period;
+pub const TURN_Z_PRIM_CODE: KeyCode = KeyCode::Comma;
+
 // move key mapping
 
 pub const DOUBLE_MOVE: KeyCode = KeyCode::Space;
@@ -82,4 +102,21 @@ pub fn move_bindings() -> HashMap<KeyCode, (GridSide, MoveDirection)> {
         (MOVE_S_CODE, (MiddleZ, Clockwise)),
         (MOVE_S_PRIM_CODE, (MiddleZ, CounterClockwise)),
     ])
-}
\ No newline at end of file
+}
+
+// whole cube turns, by the side they turn the cube like
+pub fn turn_bindings() -> HashMap<KeyCode, (GridSide, MoveDirection)> {
+    use GridSide::*;
+    use MoveDirection::*;
+
+    HashMap::from([
+        (TURN_X_CODE, (Right, Clockwise)),
+        (TURN_X_PRIM_CODE, (Right, CounterClockwise)),
+
+        (TURN_Y_CODE, (Top, Clockwise)),
+        (TURN_Y_PRIM_CODE, (Top, CounterClockwise)),
+
+        (TURN_Z_CODE, (Front, Clockwise)),
+        (TURN_Z_PRIM_CODE, (Front, CounterClockwise)),
+    ])
+}
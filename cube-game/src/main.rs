@@ -5,6 +5,7 @@ mod timer;
 mod controls;
 mod draw;
 mod args;
+mod screenshot;
 
 use pixels::Error;
 
@@ -1,40 +1,66 @@
-use std::f32::consts::FRAC_PI_2;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::time::{Duration, Instant};
 
-use cube_core::cube::core::grid::GridSide;
+use cube_core::cube::core::{grid::GridSide, orientation::Orientation};
 use cube_core::cube::slice::CubeSliceOrder;
 use cube_core::{cube::{core::grid::MoveDirection, slice::{CubeMove, CubeSlice}}, game::render::Renderable};
+use cube_core::utils::geometry::Point3D;
 use crate::game::Game;
 
 pub struct AnimatedMoveInfo {
     pub slices: [CubeSlice; 3],
     pub slice_id: usize,
-    pub current_step: u8,
+    pub start: Instant,
+    pub duration: Duration,
+    pub angle_done: f32,
     pub cube_move: CubeMove,
 }
 
+// the whole cube turning about the axis through a face
+pub struct AnimatedCubeTurn {
+    pub axis: Point3D,
+    pub angle: f32,
+    // how the cube is held once the turn is done
+    pub target: Orientation,
+    pub start: Instant,
+    pub duration: Duration,
+    pub angle_done: f32,
+}
+
+impl AnimatedCubeTurn {
+    pub fn new(axis: Point3D, direction: MoveDirection, target: Orientation, duration: Duration) -> AnimatedCubeTurn {
+        // turning clockwise seen from the face is the negative way around its axis
+        let angle = match direction {
+            MoveDirection::Clockwise => -FRAC_PI_2,
+            MoveDirection::CounterClockwise => FRAC_PI_2,
+            MoveDirection::Double => -PI,
+        };
+        AnimatedCubeTurn { axis, angle, target, start: Instant::now(), duration, angle_done: 0.0 }
+    }
+}
+
 fn render_animation_frame(game: &mut Game, slices: &[CubeSlice; 3]) {
     game.screen.clear_screen();
-    let slices_vec: Vec<&dyn Renderable> = slices.iter()
-        .map(|s| s as &dyn Renderable)
-        .collect();
-
-    game.screen.render(slices_vec);
+    game.screen.render(&slices.each_ref().map(|s| s as &dyn Renderable));
 }
 
 fn finish_animating_rotation(game: &mut Game) {
     game.controls.animated_move = None;
-    game.cube.apply_grid(&game.grid);
+    game.cube.apply_grid(game.history.grid());
 }
 
+// turns the slice as far as it should be by now, so the move takes
+// the same time no matter how often this gets called
 pub fn animate_rotation(game: &mut Game, am: &mut AnimatedMoveInfo) -> bool {
-    let angle_diff = get_angle_diff(game.args.no_steps, &am.cube_move);
-    
+    let progress = get_progress(am.start, am.duration);
+    let angle = get_move_angle(&am.cube_move) * game.args.easing.apply(progress);
+
     render_animation_frame(game, &am.slices);
     let slice_to_move = &mut am.slices[am.slice_id];
-    slice_to_move.rotate_around_own_axis(angle_diff);
-    am.current_step += 1;
+    slice_to_move.rotate_around_own_axis(angle - am.angle_done);
+    am.angle_done = angle;
 
-    if am.current_step == game.args.no_steps {
+    if progress >= 1.0 {
         finish_animating_rotation(game);
         false
     } else {
@@ -42,24 +68,50 @@ pub fn animate_rotation(game: &mut Game, am: &mut AnimatedMoveInfo) -> bool {
     }
 }
 
-fn get_angle_diff(no_steps: u8, cube_move: &CubeMove) -> f32 {
-    let mut angle_diff = FRAC_PI_2 / (no_steps as f32);
+// turns the whole cube like `animate_rotation` turns a slice; once done,
+// the cube is held exactly straight in the new orientation
+pub fn animate_cube_turn(game: &mut Game) {
+    let Some(turn) = game.controls.animated_turn.as_mut() else {
+        return;
+    };
+
+    let progress = get_progress(turn.start, turn.duration);
+    let angle = turn.angle * game.args.easing.apply(progress);
+    game.cube.rotate_around(turn.axis, angle - turn.angle_done);
+    turn.angle_done = angle;
+
+    if progress >= 1.0 {
+        game.cube.hold(turn.target);
+        game.controls.animated_turn = None;
+    }
+}
+
+fn get_progress(start: Instant, duration: Duration) -> f32 {
+    if duration.is_zero() {
+        1.0
+    } else {
+        (start.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.0)
+    }
+}
+
+fn get_move_angle(cube_move: &CubeMove) -> f32 {
+    let mut angle = FRAC_PI_2;
     
     if let MoveDirection::CounterClockwise = cube_move.direction {
-        angle_diff *= -1.0;
+        angle *= -1.0;
     }
     
     if let CubeSliceOrder::LAST = cube_move.order {
-        angle_diff *= -1.0;
+        angle *= -1.0;
     }
     
     if let MoveDirection::Double = cube_move.direction {
-        angle_diff *= 2.0;
+        angle *= 2.0;
     }
 
     if let GridSide::MiddleY = cube_move.grid_side {
-        angle_diff *= -1.0;
+        angle *= -1.0;
     }
     
-    angle_diff
+    angle
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::path::Path;
+use std::time::SystemTime;
+
+use cube_core::game::{render::Renderable, snapshot::{Image, Snapshot}};
+use log::{error, info};
+
+use crate::game::Game;
+
+// saves the frame to a PNG file in the working directory; scaled up, the
+// cube is drawn again at the bigger size rather than stretched
+pub fn save_screenshot(game: &Game) {
+    let args = &game.args;
+    let image = if args.screenshot_scale == 1 {
+        Image {
+            width: args.width as usize,
+            height: args.height as usize,
+            rgba: game.pixels.frame().to_vec(),
+        }
+    } else {
+        let scale = args.screenshot_scale as usize;
+        let snapshot = Snapshot::new(args.width as usize * scale, args.height as usize * scale)
+            .supersampled(args.samples)
+            .with_stickers(args.stickers)
+            .with_lighting(args.lighting)
+            .with_palette(args.colors.palette);
+
+        // in the middle of a move the cube already holds the grid after it,
+        // while the turning layers are what is on the screen
+        match &game.controls.animated_move {
+            Some(am) => snapshot.render_renderables(&am.borrow().slices.each_ref().map(|s| s as &dyn Renderable)),
+            None => snapshot.render_cube(&game.cube),
+        }
+    };
+
+    match image.save_screenshot(Path::new("."), SystemTime::now()) {
+        Ok(path) => info!("screenshot saved to {}", path.display()),
+        Err(e) => error!("{}", e),
+    }
+}
@@ -1,11 +1,39 @@
 use std::time::Instant;
 
+use cube_core::cube::slice::CubeMove;
+use log::info;
+
 use crate::game::Game;
 
+#[derive(Debug, Clone)]
+pub enum SolveEventKind {
+    Move(CubeMove),
+    // the inverse move done to undo the last one
+    Undo(CubeMove),
+    Redo(CubeMove),
+}
+
+#[derive(Debug, Clone)]
+pub struct SolveEvent {
+    pub time: f32,
+    pub kind: SolveEventKind,
+}
+
+impl SolveEvent {
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            SolveEventKind::Move(mv) => format!("{:.2} {}", self.time, mv.to_notation()),
+            SolveEventKind::Undo(mv) => format!("{:.2} undo ({})", self.time, mv.to_notation()),
+            SolveEventKind::Redo(mv) => format!("{:.2} redo {}", self.time, mv.to_notation()),
+        }
+    }
+}
+
 pub struct Timer {
     elapsed: f32,
     run: bool,
     instant: Instant,
+    events: Vec<SolveEvent>,
 }
 
 impl Timer {
@@ -14,6 +42,7 @@ impl Timer {
             elapsed: 0.0,
             run: false,
             instant: Instant::now(),
+            events: Vec::new(),
         }
     }
 
@@ -26,6 +55,17 @@ impl Timer {
         self.run = false;
     }
 
+    pub fn record(&mut self, kind: SolveEventKind) {
+        if self.run {
+            let time = self.instant.elapsed().as_secs_f32();
+            self.events.push(SolveEvent { time, kind });
+        }
+    }
+
+    pub fn events(&self) -> &[SolveEvent] {
+        &self.events
+    }
+
     pub fn update_elapsed(&mut self) -> f32 {
         if self.run {
             self.elapsed = self.instant.elapsed().as_secs_f32();
@@ -49,8 +89,21 @@ pub fn start_timer(game: &mut Game) {
 }
 
 pub fn stop_timer(game: &mut Game) {
-    if let Some(timer) = &mut game.timer {
+    if let Some(timer) = &mut game.timer
+        && timer.run {
         timer.stop();
+
+        let undos = timer.events().iter()
+            .filter(|e| matches!(e.kind, SolveEventKind::Undo(_)))
+            .count();
+        info!("solved in {:.2}s with {} moves and {} undos", timer.update_elapsed(), timer.events().len() - undos, undos);
+        info!("{}", timer.events().iter().map(SolveEvent::describe).collect::<Vec<String>>().join(", "));
+    }
+}
+
+pub fn record_event(game: &mut Game, kind: SolveEventKind) {
+    if let Some(timer) = &mut game.timer {
+        timer.record(kind);
     }
 }
 